@@ -1,4 +1,5 @@
-use anchor_lang::prelude::*;
+use crate::{config::*, error::*, math::*};
+use anchor_lang::{prelude::*, solana_program::hash::hash};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
 pub enum IdoState {
@@ -8,39 +9,204 @@ pub enum IdoState {
     Over,
 }
 
+/// every account starts with the version of its layout and ends with reserved space,
+/// so that fields can be added without a realloc, accounts of the first release
+/// had neither and are moved over by the `migrate_*` instructions
 #[account]
 pub struct Ido {
+    pub version: u8,
     pub bump: u8,
-    pub authority: Pubkey,
+    /// manages roles
+    pub admin: Pubkey,
+    /// starts and ends rounds
+    pub operator: Pubkey,
+    /// withdraws the IDO's USDC
+    pub treasurer: Pubkey,
     pub state: IdoState,
     pub acdm_mint: Pubkey,
     pub usdc_mint: Pubkey,
+    /// in USDC base units per whole ACDM
     pub acdm_price: u64,
     pub usdc_traded: u64,
+    /// the id of the next order or bid, which share the sequence
     pub orders: u64,
-    pub round_time: u32,
-    pub current_state_start_ts: u32,
+    /// in seconds
+    pub round_time: i64,
+    pub current_state_start_ts: i64,
     pub sale_rounds_started: u8,
+    pub config: IdoConfig,
+    pub pending_authority: Option<Pubkey>,
+    pub paused: bool,
+    pub paused_at: i64,
+    pub acdm_decimals: u8,
+    /// the next sequence number of an order or bid, see `next_sequence`
+    pub sequence: u64,
+    /// created by `migrate_ido`, so the orders and members of the first release belong to it
+    pub migrated: bool,
+    pub reserved: [u8; 120],
 }
 impl Ido {
-    pub const LEN: usize = 1 + 32 + 1 + 32 + 32 + 8 + 8 + 8 + 4 + 4 + 1;
+    pub const VERSION: u8 = 1;
+    pub const LEN: usize = 1
+        + 1
+        + 32
+        + 32
+        + 32
+        + 1
+        + 32
+        + 32
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + IdoConfig::LEN
+        + 33
+        + 1
+        + 8
+        + 1
+        + 8
+        + 1
+        + 120;
+
+    /// orders and bids are stamped with it whenever they are added or repriced,
+    /// so the side that has rested on the book the longest at its price is the lower one
+    pub fn next_sequence(&mut self) -> Result<u64> {
+        let sequence = self.sequence;
+        self.sequence = add(sequence, 1)?;
+        Ok(sequence)
+    }
+
+    /// returns the amount of ACDM to mint for the round,
+    /// rounded down so that its value never exceeds the USDC traded
+    pub fn start_sale_round(&mut self, ts: i64) -> Result<u64> {
+        self.state = IdoState::SaleRound;
+        self.current_state_start_ts = ts;
+        self.acdm_price = self
+            .config
+            .sale_price(self.sale_rounds_started, self.acdm_price)?;
+        self.sale_rounds_started = self
+            .sale_rounds_started
+            .checked_add(1)
+            .ok_or(IdoError::Overflow)?;
+
+        usdc_to_acdm(
+            self.usdc_traded,
+            self.acdm_price,
+            self.acdm_decimals,
+            Rounding::Down,
+        )
+    }
+
+    pub fn start_trade_round(&mut self, ts: i64) {
+        self.state = IdoState::TradeRound;
+        self.current_state_start_ts = ts;
+        self.usdc_traded = 0;
+    }
+
+    pub fn end(&mut self, ts: i64) {
+        self.state = IdoState::Over;
+        self.current_state_start_ts = ts;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq)]
+pub enum OrderExpiry {
+    Never,
+    At {
+        ts: i64,
+    },
+    /// when the trade round the order was added in ends
+    EndOfRound,
 }
 
 #[account]
 pub struct Order {
+    pub version: u8,
     pub bump: u8,
     pub authority: Pubkey,
+    /// in USDC base units per whole ACDM
     pub price: u64,
+    pub original_amount: u64,
+    pub filled_amount: u64,
+    pub created_ts: i64,
+    /// the number of the trade round the order was added in,
+    /// which is the number of the sale round preceding it
+    pub round: u8,
+    pub expiry: OrderExpiry,
+    /// see `Ido::next_sequence`
+    pub sequence: u64,
+    pub reserved: [u8; 22],
 }
 impl Order {
-    pub const LEN: usize = 1 + 32 + 8;
+    pub const VERSION: u8 = 1;
+    pub const LEN: usize = 1 + 1 + 32 + 8 + 8 + 8 + 8 + 1 + 9 + 8 + 22;
+
+    pub fn is_expired(&self, ido: &Ido, ts: i64) -> bool {
+        match self.expiry {
+            OrderExpiry::Never => false,
+            OrderExpiry::At { ts: expires_at } => ts >= expires_at,
+            OrderExpiry::EndOfRound => {
+                ido.state != IdoState::TradeRound || ido.sale_rounds_started != self.round
+            }
+        }
+    }
+}
+
+/// a buy order escrowing the USDC for `original_amount` of ACDM
+#[account]
+pub struct Bid {
+    pub version: u8,
+    pub bump: u8,
+    pub authority: Pubkey,
+    /// in USDC base units per whole ACDM
+    pub price: u64,
+    pub original_amount: u64,
+    pub filled_amount: u64,
+    pub created_ts: i64,
+    pub round: u8,
+    /// see `Ido::next_sequence`
+    pub sequence: u64,
+    pub reserved: [u8; 24],
+}
+impl Bid {
+    pub const VERSION: u8 = 1;
+    pub const LEN: usize = 1 + 1 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 24;
+}
+
+#[account]
+pub struct ReferralCode {
+    pub version: u8,
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub reserved: [u8; 32],
+}
+impl ReferralCode {
+    pub const VERSION: u8 = 1;
+    pub const LEN: usize = 1 + 1 + 32 + 32;
+    pub const MAX_CODE_LEN: usize = 32;
+
+    /// the code is hashed, as a seed can't be longer than 32 bytes
+    pub fn seed(code: &str) -> [u8; 32] {
+        hash(code.as_bytes()).to_bytes()
+    }
 }
 
 #[account]
 pub struct Member {
+    pub version: u8,
     pub bump: u8,
     pub referer: Option<Pubkey>,
+    pub rewards: u64,
+    pub referees: u32,
+    /// USDC earned per referral level, the IDL requires a literal `MAX_REFERRAL_DEPTH` here
+    pub rewards_earned: [u64; 5],
+    pub acdm_bought: u64,
+    pub acdm_sold: u64,
+    pub reserved: [u8; 64],
 }
 impl Member {
-    pub const LEN: usize = 1 + 33;
+    pub const VERSION: u8 = 1;
+    pub const LEN: usize = 1 + 1 + 33 + 8 + 4 + 8 * MAX_REFERRAL_DEPTH + 8 + 8 + 64;
 }
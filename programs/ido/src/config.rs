@@ -1,6 +1,143 @@
-pub const INITIAL_ISSUE: u64 = 10_000;
-pub const INITIAL_PRICE: u64 = 100_000;
+use crate::{error::*, math::*};
+use anchor_lang::prelude::*;
 
-pub const fn sale_price_formula(prev_price: u64) -> u64 {
-    prev_price * 103 / 100 + INITIAL_PRICE * 2 / 5
+pub const MAX_PRICE_TABLE_LEN: usize = 8;
+pub const MAX_BPS: u16 = 10_000;
+pub const MAX_REFERRAL_DEPTH: usize = 5;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum PriceCurve {
+    /// `prev_price * numerator / denominator + increment`
+    Geometric {
+        numerator: u64,
+        denominator: u64,
+        increment: u64,
+    },
+    /// `prev_price + step`
+    Linear { step: u64 },
+    /// `prev_price * (10_000 + growth_bps) / 10_000`
+    Percentage { growth_bps: u16 },
+    /// `initial_price` in every round
+    Fixed,
+    /// `prices[i]` is the price of the `i + 2`-th sale round,
+    /// the last price is kept for all following rounds
+    Table { prices: Vec<u64> },
+}
+impl PriceCurve {
+    pub const LEN: usize = 1 + 4 + 8 * MAX_PRICE_TABLE_LEN;
+
+    fn validate(&self) -> Result<()> {
+        match self {
+            PriceCurve::Geometric { denominator, .. } if *denominator == 0 => {
+                err!(IdoError::InvalidConfig)
+            }
+            PriceCurve::Table { prices }
+                if prices.is_empty()
+                    || prices.len() > MAX_PRICE_TABLE_LEN
+                    || prices.contains(&0) =>
+            {
+                err!(IdoError::InvalidConfig)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `round` is the zero-based index of the sale round being started, must be nonzero
+    fn price(&self, round: u8, prev_price: u64, initial_price: u64) -> Result<u64> {
+        let price = match self {
+            PriceCurve::Geometric {
+                numerator,
+                denominator,
+                increment,
+            } => add(
+                mul_div(prev_price, *numerator, *denominator, Rounding::Down)?,
+                *increment,
+            )?,
+            PriceCurve::Linear { step } => add(prev_price, *step)?,
+            PriceCurve::Percentage { growth_bps } => mul_div(
+                prev_price,
+                MAX_BPS as u64 + *growth_bps as u64,
+                MAX_BPS as u64,
+                Rounding::Down,
+            )?,
+            PriceCurve::Fixed => initial_price,
+            PriceCurve::Table { prices } => {
+                let i = (round as usize).saturating_sub(1).min(prices.len() - 1);
+                prices[i]
+            }
+        };
+
+        if price == 0 {
+            return err!(IdoError::InvalidConfig);
+        }
+
+        Ok(price)
+    }
+}
+
+/// all fees are in basis points of the purchase's USDC amount,
+/// referer fees are listed per referral level starting from the direct referer,
+/// so their length is the depth of the referral chain walked for that round type
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FeeSchedule {
+    /// paid to the buyer's referers during the sale round, the rest goes to the IDO
+    pub sale_referer_bps: Vec<u16>,
+    /// withheld from the seller during the trade round
+    pub trade_fee_bps: u16,
+    /// paid to the seller's referers out of the trade fee, the rest goes to the IDO
+    pub trade_referer_bps: Vec<u16>,
+}
+impl FeeSchedule {
+    pub const LEN: usize = (4 + 2 * MAX_REFERRAL_DEPTH) + 2 + (4 + 2 * MAX_REFERRAL_DEPTH);
+
+    fn validate(&self) -> Result<()> {
+        if self.sale_referer_bps.len() > MAX_REFERRAL_DEPTH
+            || self.trade_referer_bps.len() > MAX_REFERRAL_DEPTH
+        {
+            return err!(IdoError::InvalidConfig);
+        }
+
+        let sale_referer_bps: u32 = self.sale_referer_bps.iter().map(|&bps| bps as u32).sum();
+        let trade_referer_bps: u32 = self.trade_referer_bps.iter().map(|&bps| bps as u32).sum();
+
+        if sale_referer_bps > MAX_BPS as u32
+            || self.trade_fee_bps > MAX_BPS
+            || trade_referer_bps > self.trade_fee_bps as u32
+        {
+            return err!(IdoError::InvalidConfig);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct IdoConfig {
+    /// in ACDM base units
+    pub initial_issue: u64,
+    /// in USDC base units per whole ACDM
+    pub initial_price: u64,
+    pub price_curve: PriceCurve,
+    pub fees: FeeSchedule,
+}
+impl IdoConfig {
+    pub const LEN: usize = 8 + 8 + PriceCurve::LEN + FeeSchedule::LEN;
+
+    pub fn validate(&self) -> Result<()> {
+        if self.initial_issue == 0 || self.initial_price == 0 {
+            return err!(IdoError::InvalidConfig);
+        }
+
+        self.price_curve.validate()?;
+        self.fees.validate()
+    }
+
+    pub fn sale_price(&self, round: u8, prev_price: u64) -> Result<u64> {
+        if round == 0 {
+            return Ok(self.initial_price);
+        }
+
+        self.price_curve
+            .price(round, prev_price, self.initial_price)
+    }
 }
@@ -21,15 +21,80 @@ pub enum IdoError {
     #[msg("Referer's member account is not provided")]
     RefererMemberAccountNotProvided,
     /// 6006 0x1776
+    /// unused since referral fees are escrowed, kept so that later codes don't change
     #[msg("Referer's token account is not provided")]
     RefererTokenAccountNotProvided,
     /// 6007 0x1777
     #[msg("Supplied account is not the PDA of user's referer")]
     RefererPda,
     /// 6008 0x1778
+    /// unused since referral fees are escrowed, kept so that later codes don't change
     #[msg("Referer must own the token account to get fees")]
     RefererOwner,
     /// 6009 0x1779
     #[msg("Ido is over")]
     IdoIsOver,
+    /// 6010 0x177a
+    #[msg("Invalid IDO configuration")]
+    InvalidConfig,
+    /// 6011 0x177b
+    #[msg("Member cannot refer themselves")]
+    SelfReferral,
+    /// 6012 0x177c
+    /// unused as cycles can't form, kept so that later codes don't change
+    #[msg("Referral chain leads back to the member being registered")]
+    ReferralCycle,
+    /// 6013 0x177d
+    #[msg("Referral code must be from 1 to 32 bytes long")]
+    InvalidReferralCode,
+    /// 6014 0x177e
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    /// 6015 0x177f
+    #[msg("Ido is paused")]
+    Paused,
+    /// 6016 0x1780
+    #[msg("Ido is not paused")]
+    NotPaused,
+    /// 6017 0x1781
+    /// unused since the number of sale rounds isn't limited, kept so that later codes don't change
+    #[msg("All sale rounds have been held")]
+    NoSaleRoundsLeft,
+    /// 6018 0x1782
+    /// unused since the IDO PDA is made the mint authority at initialization, kept so that later codes don't change
+    #[msg("Ido is not the ACDM mint authority")]
+    AcdmMintAuthority,
+    /// 6019 0x1783
+    #[msg("Division by zero")]
+    DivisionByZero,
+    /// 6020 0x1784
+    #[msg("Account already has the current layout")]
+    AlreadyMigrated,
+    /// 6021 0x1785
+    #[msg("Order has expired")]
+    OrderExpired,
+    /// 6022 0x1786
+    #[msg("Order has not expired")]
+    OrderNotExpired,
+    /// 6023 0x1787
+    #[msg("Order expiry is in the past")]
+    InvalidOrderExpiry,
+    /// 6024 0x1788
+    #[msg("Amount exceeds what is left of the bid")]
+    BidAmountExceeded,
+    /// 6025 0x1789
+    #[msg("Bid price is below the order price")]
+    OrdersDontCross,
+    /// 6026 0x178a
+    #[msg("Order or bid has nothing left to match")]
+    NothingToMatch,
+    /// 6027 0x178b
+    #[msg("Orders are not sorted by price")]
+    OrdersNotSorted,
+    /// 6028 0x178c
+    #[msg("Average price exceeds the limit")]
+    PriceLimitExceeded,
+    /// 6029 0x178d
+    #[msg("IDO was not migrated from the first release")]
+    NotMigrated,
 }
@@ -1,14 +1,61 @@
-use crate::{account::*, error::*};
+use crate::{account::*, error::*, transfer::*};
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, Burn, CloseAccount, Mint, MintTo, TokenAccount, TokenInterface,
+};
 
-pub(crate) fn round_time_over(ido: &Ido, ts: u32) -> Result<()> {
-    if ts - ido.current_state_start_ts < ido.round_time {
+pub(crate) fn round_time_over(ido: &Ido, ts: i64) -> Result<()> {
+    // a clock going backwards only makes the round last longer
+    if ts.saturating_sub(ido.current_state_start_ts) < ido.round_time {
         err!(IdoError::CannotEndRound)
     } else {
         Ok(())
     }
 }
 
+pub(crate) fn can_start_sale_round(ido: &Ido, ts: i64) -> Result<()> {
+    match ido.state {
+        IdoState::NotStarted => Ok(()),
+        IdoState::SaleRound => err!(IdoError::RoundAlreadyStarted),
+        IdoState::TradeRound => round_time_over(ido, ts),
+        IdoState::Over => err!(IdoError::IdoIsOver),
+    }
+}
+
+/// the sale round may end early once all of its ACDM is sold
+pub(crate) fn can_start_trade_round(ido: &Ido, ido_acdm_amount: u64, ts: i64) -> Result<()> {
+    match ido.state {
+        IdoState::NotStarted => err!(IdoError::NotSaleRound),
+        IdoState::SaleRound => {
+            if ido_acdm_amount == 0 {
+                return Ok(());
+            }
+
+            round_time_over(ido, ts)
+        }
+        IdoState::TradeRound => err!(IdoError::RoundAlreadyStarted),
+        IdoState::Over => err!(IdoError::IdoIsOver),
+    }
+}
+
+pub(crate) fn can_end_ido(ido: &Ido, ts: i64) -> Result<()> {
+    match ido.state {
+        IdoState::NotStarted => err!(IdoError::NotTradeRound),
+        IdoState::SaleRound => err!(IdoError::NotTradeRound),
+        IdoState::TradeRound => round_time_over(ido, ts),
+        IdoState::Over => err!(IdoError::IdoIsOver),
+    }
+}
+
+/// only for actions that add risk, so users can always take their funds out
+pub(crate) fn not_paused(ido: &Ido) -> Result<()> {
+    if ido.paused {
+        err!(IdoError::Paused)
+    } else {
+        Ok(())
+    }
+}
+
 pub(crate) fn is_sale_round(ido: &Ido) -> Result<()> {
     match ido.state {
         IdoState::NotStarted => err!(IdoError::NotSaleRound),
@@ -26,3 +73,87 @@ pub(crate) fn is_trade_round(ido: &Ido) -> Result<()> {
         IdoState::Over => err!(IdoError::IdoIsOver),
     }
 }
+
+/// sends what is left of the order's ACDM to the seller and closes its escrow,
+/// returning the rent to the seller, the order account itself is closed by the caller
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn return_order_escrow<'info>(
+    ido: &Account<'info, Ido>,
+    order: &Account<'info, Order>,
+    id: u64,
+    acdm_mint: &InterfaceAccount<'info, Mint>,
+    order_acdm: &InterfaceAccount<'info, TokenAccount>,
+    seller: &AccountInfo<'info>,
+    seller_acdm: &InterfaceAccount<'info, TokenAccount>,
+    acdm_token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let ido = ido.key();
+    let signer: &[&[&[u8]]] = &[&[
+        b"order".as_ref(),
+        ido.as_ref(),
+        &id.to_le_bytes(),
+        &[order.bump],
+    ]];
+
+    if order_acdm.amount != 0 {
+        let cpi_accounts = TransferChecked {
+            from: order_acdm.to_account_info(),
+            mint: acdm_mint.to_account_info(),
+            to: seller_acdm.to_account_info(),
+            authority: order.to_account_info(),
+        };
+        let cpi_program = acdm_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        transfer_checked(cpi_ctx, order_acdm.amount, acdm_mint.decimals)?;
+    }
+
+    let cpi_accounts = CloseAccount {
+        account: order_acdm.to_account_info(),
+        destination: seller.clone(),
+        authority: order.to_account_info(),
+    };
+    let cpi_program = acdm_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    close_account(cpi_ctx, &acdm_mint.to_account_info())
+}
+
+/// mints the ACDM of a sale round to the IDO, which is the mint authority
+pub(crate) fn mint_ido_acdm<'info>(
+    ido: &Account<'info, Ido>,
+    acdm_mint: &InterfaceAccount<'info, Mint>,
+    ido_acdm: &InterfaceAccount<'info, TokenAccount>,
+    acdm_token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    let signer: &[&[&[u8]]] = &[&[b"ido".as_ref(), ido.acdm_mint.as_ref(), &[ido.bump]]];
+    let cpi_accounts = MintTo {
+        mint: acdm_mint.to_account_info(),
+        to: ido_acdm.to_account_info(),
+        authority: ido.to_account_info(),
+    };
+    let cpi_program = acdm_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token_interface::mint_to(cpi_ctx, amount)
+}
+
+/// burns the ACDM left unsold by a sale round
+pub(crate) fn burn_ido_acdm<'info>(
+    ido: &Account<'info, Ido>,
+    acdm_mint: &InterfaceAccount<'info, Mint>,
+    ido_acdm: &InterfaceAccount<'info, TokenAccount>,
+    acdm_token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    if ido_acdm.amount == 0 {
+        return Ok(());
+    }
+
+    let signer: &[&[&[u8]]] = &[&[b"ido".as_ref(), ido.acdm_mint.as_ref(), &[ido.bump]]];
+    let cpi_accounts = Burn {
+        mint: acdm_mint.to_account_info(),
+        from: ido_acdm.to_account_info(),
+        authority: ido.to_account_info(),
+    };
+    let cpi_program = acdm_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token_interface::burn(cpi_ctx, ido_acdm.amount)
+}
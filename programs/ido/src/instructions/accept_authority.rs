@@ -0,0 +1,30 @@
+use crate::{account::*, error::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"ido", ido.acdm_mint.as_ref()],
+        bump = ido.bump,
+        constraint = ido.pending_authority == Some(new_authority.key()) @ IdoError::NotPendingAuthority,
+    )]
+    ido: Account<'info, Ido>,
+    new_authority: Signer<'info>,
+}
+
+pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    ctx.accounts.ido.admin = ctx.accounts.new_authority.key();
+    ctx.accounts.ido.pending_authority = None;
+
+    emit!(AcceptAuthorityEvent {
+        authority: ctx.accounts.new_authority.key(),
+    });
+
+    Ok(())
+}
+
+#[event]
+struct AcceptAuthorityEvent {
+    authority: Pubkey,
+}
@@ -0,0 +1,102 @@
+use crate::{account::*, helpers::*, math::*, transfer::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct AddBid<'info> {
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(
+        init,
+        payer = buyer,
+        seeds = [b"bid", ido.key().as_ref(), ido.orders.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Bid::LEN,
+    )]
+    bid: Account<'info, Bid>,
+    #[account(address = ido.usdc_mint)]
+    usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::authority = bid,
+        associated_token::mint = usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    bid_usdc: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    buyer: Signer<'info>,
+    /// only members may bid, as with buying in the sale round
+    #[account(
+        seeds = [b"member", ido.key().as_ref(), buyer.key().as_ref()],
+        bump = buyer_member.bump,
+    )]
+    buyer_member: Account<'info, Member>,
+    #[account(mut)]
+    buyer_usdc: InterfaceAccount<'info, TokenAccount>,
+    rent: Sysvar<'info, Rent>,
+    usdc_token_program: Interface<'info, TokenInterface>,
+    system_program: Program<'info, System>,
+}
+impl<'info> AddBid<'info> {
+    fn transfer_usdc(&self, amount: u64) -> Result<u64> {
+        let cpi_accounts = TransferChecked {
+            from: self.buyer_usdc.to_account_info(),
+            mint: self.usdc_mint.to_account_info(),
+            to: self.bid_usdc.to_account_info(),
+            authority: self.buyer.to_account_info(),
+        };
+        let cpi_program = self.usdc_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        transfer_checked_received(cpi_ctx, amount, self.usdc_mint.decimals)
+    }
+}
+
+pub fn add_bid(ctx: Context<AddBid>, acdm_amount: u64, acdm_price: u64) -> Result<()> {
+    not_paused(&ctx.accounts.ido)?;
+    is_trade_round(&ctx.accounts.ido)?;
+
+    // fills round down, so the escrow always covers the whole bid
+    let usdc_amount = acdm_to_usdc(
+        acdm_amount,
+        acdm_price,
+        ctx.accounts.ido.acdm_decimals,
+        Rounding::Up,
+    )?;
+    let usdc_amount_received = ctx.accounts.transfer_usdc(usdc_amount)?;
+
+    // the bid shrinks to what its escrow covers if the USDC mint withholds a transfer fee
+    let acdm_amount = acdm_amount.min(usdc_to_acdm(
+        usdc_amount_received,
+        acdm_price,
+        ctx.accounts.ido.acdm_decimals,
+        Rounding::Down,
+    )?);
+
+    ctx.accounts.bid.version = Bid::VERSION;
+    ctx.accounts.bid.bump = ctx.bumps.bid;
+    ctx.accounts.bid.authority = ctx.accounts.buyer.key();
+    ctx.accounts.bid.price = acdm_price;
+    ctx.accounts.bid.original_amount = acdm_amount;
+    ctx.accounts.bid.created_ts = Clock::get()?.unix_timestamp;
+    ctx.accounts.bid.round = ctx.accounts.ido.sale_rounds_started;
+    ctx.accounts.bid.sequence = ctx.accounts.ido.next_sequence()?;
+
+    emit!(AddBidEvent {
+        id: ctx.accounts.ido.orders,
+        buyer: ctx.accounts.buyer.key(),
+        amount: acdm_amount,
+        price: acdm_price,
+    });
+
+    ctx.accounts.ido.orders = add(ctx.accounts.ido.orders, 1)?;
+
+    Ok(())
+}
+
+#[event]
+struct AddBidEvent {
+    id: u64,
+    buyer: Pubkey,
+    amount: u64,
+    price: u64,
+}
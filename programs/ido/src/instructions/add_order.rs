@@ -1,61 +1,88 @@
-use crate::{account::*, helpers::*};
+use crate::{account::*, error::*, helpers::*, math::*, transfer::*};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct AddOrder<'info> {
-    #[account(mut, seeds = [b"ido"], bump = ido.bump)]
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
     ido: Account<'info, Ido>,
     #[account(
         init,
         payer = seller,
-        seeds = [b"order", ido.orders.to_le_bytes().as_ref()],
+        seeds = [b"order", ido.key().as_ref(), ido.orders.to_le_bytes().as_ref()],
         bump,
         space = 8 + Order::LEN,
     )]
     order: Account<'info, Order>,
-    #[account(mut, address = ido.acdm_mint)]
-    acdm_mint: Account<'info, Mint>,
-    #[account(mut, associated_token::authority = order, associated_token::mint = acdm_mint)]
-    order_acdm: Account<'info, TokenAccount>,
+    #[account(address = ido.acdm_mint)]
+    acdm_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::authority = order,
+        associated_token::mint = acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    order_acdm: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     seller: Signer<'info>,
     #[account(mut)]
-    seller_acdm: Account<'info, TokenAccount>,
+    seller_acdm: InterfaceAccount<'info, TokenAccount>,
     rent: Sysvar<'info, Rent>,
-    token_program: Program<'info, Token>,
+    acdm_token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
 }
 impl<'info> AddOrder<'info> {
-    fn transfer_acdm(&self, amount: u64) -> Result<()> {
-        let cpi_accounts = Transfer {
+    fn transfer_acdm(&self, amount: u64) -> Result<u64> {
+        let cpi_accounts = TransferChecked {
             from: self.seller_acdm.to_account_info(),
+            mint: self.acdm_mint.to_account_info(),
             to: self.order_acdm.to_account_info(),
             authority: self.seller.to_account_info(),
         };
-        let cpi_program = self.token_program.to_account_info();
+        let cpi_program = self.acdm_token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, amount)
+        transfer_checked_received(cpi_ctx, amount, self.acdm_mint.decimals)
     }
 }
 
-pub fn add_order(ctx: Context<AddOrder>, acdm_amount: u64, acdm_price: u64) -> Result<()> {
+pub fn add_order(
+    ctx: Context<AddOrder>,
+    acdm_amount: u64,
+    acdm_price: u64,
+    expiry: OrderExpiry,
+) -> Result<()> {
+    let ts = Clock::get()?.unix_timestamp;
+
+    not_paused(&ctx.accounts.ido)?;
     is_trade_round(&ctx.accounts.ido)?;
+    if let OrderExpiry::At { ts: expires_at } = expiry {
+        if expires_at <= ts {
+            return err!(IdoError::InvalidOrderExpiry);
+        }
+    }
 
-    ctx.accounts.transfer_acdm(acdm_amount)?;
+    // less than `acdm_amount` if the ACDM mint withholds a transfer fee
+    let acdm_amount = ctx.accounts.transfer_acdm(acdm_amount)?;
 
-    ctx.accounts.order.bump = *ctx.bumps.get("order").unwrap();
+    ctx.accounts.order.version = Order::VERSION;
+    ctx.accounts.order.bump = ctx.bumps.order;
     ctx.accounts.order.authority = ctx.accounts.seller.key();
     ctx.accounts.order.price = acdm_price;
+    ctx.accounts.order.original_amount = acdm_amount;
+    ctx.accounts.order.created_ts = ts;
+    ctx.accounts.order.round = ctx.accounts.ido.sale_rounds_started;
+    ctx.accounts.order.sequence = ctx.accounts.ido.next_sequence()?;
+    ctx.accounts.order.expiry = expiry;
 
     emit!(AddOrderEvent {
         id: ctx.accounts.ido.orders,
         seller: ctx.accounts.seller.key(),
         amount: acdm_amount,
         price: acdm_price,
+        expiry,
     });
 
-    ctx.accounts.ido.orders += 1;
+    ctx.accounts.ido.orders = add(ctx.accounts.ido.orders, 1)?;
 
     Ok(())
 }
@@ -66,4 +93,5 @@ struct AddOrderEvent {
     seller: Pubkey,
     amount: u64,
     price: u64,
+    expiry: OrderExpiry,
 }
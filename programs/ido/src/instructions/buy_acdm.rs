@@ -1,66 +1,98 @@
-use crate::{account::*, error::*, helpers::*, referral::*};
+use crate::{account::*, helpers::*, math::*, referral::*, transfer::*};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct BuyAcdm<'info> {
-    #[account(seeds = [b"ido"], bump = ido.bump)]
+    #[account(seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
     ido: Account<'info, Ido>,
-    #[account(mut, associated_token::authority = ido, associated_token::mint = ido.acdm_mint)]
-    ido_acdm: Account<'info, TokenAccount>,
-    #[account(mut, associated_token::authority = ido, associated_token::mint = ido.usdc_mint)]
-    ido_usdc: Account<'info, TokenAccount>,
+    #[account(address = ido.acdm_mint)]
+    acdm_mint: InterfaceAccount<'info, Mint>,
+    #[account(address = ido.usdc_mint)]
+    usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = ido.acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    ido_acdm: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = ido.usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    ido_usdc: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, seeds = [b"referral_usdc", ido.key().as_ref()], bump)]
+    referral_usdc: InterfaceAccount<'info, TokenAccount>,
     buyer: Signer<'info>,
-    #[account(seeds = [b"member", buyer.key().as_ref()], bump = buyer_member.bump)]
+    #[account(
+        mut,
+        seeds = [b"member", ido.key().as_ref(), buyer.key().as_ref()],
+        bump = buyer_member.bump,
+    )]
     buyer_member: Account<'info, Member>,
     #[account(mut)]
-    buyer_acdm: Account<'info, TokenAccount>,
+    buyer_acdm: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    buyer_usdc: Account<'info, TokenAccount>,
-    token_program: Program<'info, Token>,
+    buyer_usdc: InterfaceAccount<'info, TokenAccount>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+    usdc_token_program: Interface<'info, TokenInterface>,
 }
 impl<'info> BuyAcdm<'info> {
-    fn transfer_acdm(&self, amount: u64) -> Result<()> {
-        let signer: &[&[&[u8]]] = &[&[b"ido".as_ref(), &[self.ido.bump]]];
-        let cpi_accounts = Transfer {
+    fn transfer_acdm(&self, amount: u64) -> Result<u64> {
+        let signer: &[&[&[u8]]] = &[&[
+            b"ido".as_ref(),
+            self.ido.acdm_mint.as_ref(),
+            &[self.ido.bump],
+        ]];
+        let cpi_accounts = TransferChecked {
             from: self.ido_acdm.to_account_info(),
+            mint: self.acdm_mint.to_account_info(),
             to: self.buyer_acdm.to_account_info(),
             authority: self.ido.to_account_info(),
         };
-        let cpi_program = self.token_program.to_account_info();
+        let cpi_program = self.acdm_token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-        token::transfer(cpi_ctx, amount)
+        transfer_checked_received(cpi_ctx, amount, self.acdm_mint.decimals)
     }
 }
 
 pub fn buy_acdm<'info>(
-    ctx: Context<'_, '_, '_, 'info, BuyAcdm<'info>>,
+    ctx: Context<'_, '_, 'info, 'info, BuyAcdm<'info>>,
     acdm_amount: u64,
 ) -> Result<()> {
+    not_paused(&ctx.accounts.ido)?;
     is_sale_round(&ctx.accounts.ido)?;
 
-    let usdc_amount_to_ido = acdm_amount
-        .checked_mul(ctx.accounts.ido.acdm_price)
-        .ok_or(IdoError::Overflow)?; // 100%
-    let usdc_amount_to_referer = usdc_amount_to_ido / 20; // 5%
-    let usdc_amount_to_referer2 = usdc_amount_to_ido
-        .checked_mul(3)
-        .ok_or(IdoError::Overflow)?
-        / 100; // 3%
+    let usdc_amount_total = acdm_to_usdc(
+        acdm_amount,
+        ctx.accounts.ido.acdm_price,
+        ctx.accounts.ido.acdm_decimals,
+        Rounding::Up,
+    )?;
 
     send_to_referers_and_ido(
-        usdc_amount_to_ido,
-        usdc_amount_to_referer,
-        usdc_amount_to_referer2,
+        usdc_amount_total,
+        usdc_amount_total,
+        &ctx.accounts.ido.config.fees.sale_referer_bps,
+        ctx.accounts.ido.key(),
         &ctx.accounts.buyer_member,
-        &ctx.accounts.buyer,
+        &ctx.accounts.usdc_mint,
         &ctx.accounts.buyer_usdc,
+        &ctx.accounts.buyer.to_account_info(),
+        &[],
         &ctx.accounts.ido_usdc,
-        &ctx.accounts.token_program,
+        &ctx.accounts.referral_usdc,
+        &ctx.accounts.usdc_token_program,
         ctx.remaining_accounts,
     )?;
 
-    ctx.accounts.transfer_acdm(acdm_amount)?;
+    let acdm_amount_received = ctx.accounts.transfer_acdm(acdm_amount)?;
+
+    ctx.accounts.buyer_member.acdm_bought =
+        add(ctx.accounts.buyer_member.acdm_bought, acdm_amount_received)?;
 
     emit!(BuyAcdmEvent {
         buyer: ctx.accounts.buyer.key(),
@@ -0,0 +1,63 @@
+use crate::{account::*, transfer::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(
+        mut,
+        seeds = [b"member", ido.key().as_ref(), authority.key().as_ref()],
+        bump = member.bump,
+    )]
+    member: Account<'info, Member>,
+    authority: Signer<'info>,
+    #[account(address = ido.usdc_mint)]
+    usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, seeds = [b"referral_usdc", ido.key().as_ref()], bump)]
+    referral_usdc: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = ido.usdc_mint)]
+    to: InterfaceAccount<'info, TokenAccount>,
+    usdc_token_program: Interface<'info, TokenInterface>,
+}
+impl<'info> ClaimReferralRewards<'info> {
+    fn transfer(&self, amount: u64) -> Result<()> {
+        let signer: &[&[&[u8]]] = &[&[
+            b"ido".as_ref(),
+            self.ido.acdm_mint.as_ref(),
+            &[self.ido.bump],
+        ]];
+        let cpi_accounts = TransferChecked {
+            from: self.referral_usdc.to_account_info(),
+            mint: self.usdc_mint.to_account_info(),
+            to: self.to.to_account_info(),
+            authority: self.ido.to_account_info(),
+        };
+        let cpi_program = self.usdc_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        transfer_checked(cpi_ctx, amount, self.usdc_mint.decimals)
+    }
+}
+
+pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+    let amount = ctx.accounts.member.rewards;
+
+    if amount != 0 {
+        ctx.accounts.transfer(amount)?;
+        ctx.accounts.member.rewards = 0;
+    }
+
+    emit!(ClaimReferralRewardsEvent {
+        authority: ctx.accounts.authority.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct ClaimReferralRewardsEvent {
+    authority: Pubkey,
+    amount: u64,
+}
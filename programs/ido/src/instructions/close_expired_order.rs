@@ -0,0 +1,64 @@
+use crate::{account::*, error::*, helpers::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CloseExpiredOrder<'info> {
+    #[account(seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(
+        mut,
+        seeds = [b"order", ido.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = order.bump,
+        close = seller,
+    )]
+    order: Account<'info, Order>,
+    #[account(mut, address = ido.acdm_mint)]
+    acdm_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::authority = order,
+        associated_token::mint = ido.acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    order_acdm: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK:
+    #[account(mut, address = order.authority)]
+    seller: UncheckedAccount<'info>,
+    #[account(mut, token::authority = order.authority, token::mint = ido.acdm_mint)]
+    seller_acdm: InterfaceAccount<'info, TokenAccount>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+}
+
+/// permissionless, so expired orders don't have to wait for their sellers
+pub fn close_expired_order(ctx: Context<CloseExpiredOrder>, id: u64) -> Result<()> {
+    let ts = Clock::get()?.unix_timestamp;
+
+    if !ctx.accounts.order.is_expired(&ctx.accounts.ido, ts) {
+        return err!(IdoError::OrderNotExpired);
+    }
+
+    let amount = ctx.accounts.order_acdm.amount;
+    let accounts = &ctx.accounts;
+    return_order_escrow(
+        &accounts.ido,
+        &accounts.order,
+        id,
+        &accounts.acdm_mint,
+        &accounts.order_acdm,
+        &accounts.seller.to_account_info(),
+        &accounts.seller_acdm,
+        &accounts.acdm_token_program,
+    )?;
+
+    emit!(CloseExpiredOrderEvent { id, amount });
+
+    Ok(())
+}
+
+#[event]
+struct CloseExpiredOrderEvent {
+    id: u64,
+    amount: u64,
+}
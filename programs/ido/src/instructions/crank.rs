@@ -0,0 +1,72 @@
+use crate::{account::*, error::*, helpers::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct Crank<'info> {
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(mut, address = ido.acdm_mint)]
+    acdm_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    ido_acdm: InterfaceAccount<'info, TokenAccount>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+}
+
+/// advances the IDO to its next state once the current round's time is over,
+/// the first sale round is still started by the operator
+pub fn crank(ctx: Context<Crank>) -> Result<()> {
+    let ts = Clock::get()?.unix_timestamp;
+
+    not_paused(&ctx.accounts.ido)?;
+
+    let from = ctx.accounts.ido.state;
+    let mut minted_amount = 0;
+    match from {
+        IdoState::NotStarted => return err!(IdoError::NotSaleRound),
+        IdoState::SaleRound => {
+            can_start_trade_round(&ctx.accounts.ido, ctx.accounts.ido_acdm.amount, ts)?;
+            ctx.accounts.ido.start_trade_round(ts);
+            burn_ido_acdm(
+                &ctx.accounts.ido,
+                &ctx.accounts.acdm_mint,
+                &ctx.accounts.ido_acdm,
+                &ctx.accounts.acdm_token_program,
+            )?;
+        }
+        IdoState::TradeRound => {
+            can_start_sale_round(&ctx.accounts.ido, ts)?;
+            minted_amount = ctx.accounts.ido.start_sale_round(ts)?;
+            mint_ido_acdm(
+                &ctx.accounts.ido,
+                &ctx.accounts.acdm_mint,
+                &ctx.accounts.ido_acdm,
+                &ctx.accounts.acdm_token_program,
+                minted_amount,
+            )?;
+        }
+        IdoState::Over => return err!(IdoError::IdoIsOver),
+    }
+
+    emit!(CrankEvent {
+        from,
+        to: ctx.accounts.ido.state,
+        acdm_price: ctx.accounts.ido.acdm_price,
+        minted_amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct CrankEvent {
+    from: IdoState,
+    to: IdoState,
+    acdm_price: u64,
+    minted_amount: u64,
+}
@@ -0,0 +1,45 @@
+use crate::{account::*, error::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(code: String)]
+pub struct CreateReferralCode<'info> {
+    #[account(seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(seeds = [b"member", ido.key().as_ref(), authority.key().as_ref()], bump = member.bump)]
+    member: Account<'info, Member>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"code", ido.key().as_ref(), ReferralCode::seed(&code).as_ref()],
+        bump,
+        space = 8 + ReferralCode::LEN,
+    )]
+    referral_code: Account<'info, ReferralCode>,
+    #[account(mut)]
+    authority: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+pub fn create_referral_code(ctx: Context<CreateReferralCode>, code: String) -> Result<()> {
+    if code.is_empty() || code.len() > ReferralCode::MAX_CODE_LEN {
+        return err!(IdoError::InvalidReferralCode);
+    }
+
+    ctx.accounts.referral_code.version = ReferralCode::VERSION;
+    ctx.accounts.referral_code.bump = ctx.bumps.referral_code;
+    ctx.accounts.referral_code.authority = ctx.accounts.authority.key();
+
+    emit!(CreateReferralCodeEvent {
+        authority: ctx.accounts.authority.key(),
+        code,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct CreateReferralCodeEvent {
+    authority: Pubkey,
+    code: String,
+}
@@ -1,31 +1,21 @@
-use crate::{account::*, error::*, helpers::*};
+use crate::{account::*, helpers::*};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct EndIdo<'info> {
-    #[account(mut, seeds = [b"ido"], bump = ido.bump)]
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
     ido: Account<'info, Ido>,
-    #[account(address = ido.authority)]
-    ido_authority: Signer<'info>,
-}
-impl<'info> EndIdo<'info> {
-    fn can_end_ido(&self, ts: u32) -> Result<()> {
-        match self.ido.state {
-            IdoState::NotStarted => err!(IdoError::NotTradeRound),
-            IdoState::SaleRound => err!(IdoError::NotTradeRound),
-            IdoState::TradeRound => round_time_over(&self.ido, ts),
-            IdoState::Over => err!(IdoError::IdoIsOver),
-        }
-    }
+    #[account(address = ido.operator)]
+    operator: Signer<'info>,
 }
 
 pub fn end_ido(ctx: Context<EndIdo>) -> Result<()> {
-    let ts = Clock::get()?.unix_timestamp as u32;
+    let ts = Clock::get()?.unix_timestamp;
 
-    ctx.accounts.can_end_ido(ts)?;
+    not_paused(&ctx.accounts.ido)?;
+    can_end_ido(&ctx.accounts.ido, ts)?;
 
-    ctx.accounts.ido.state = IdoState::Over;
-    ctx.accounts.ido.current_state_start_ts = ts;
+    ctx.accounts.ido.end(ts);
 
     emit!(EndIdoEvent {});
 
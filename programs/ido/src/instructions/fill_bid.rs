@@ -0,0 +1,176 @@
+use crate::{account::*, error::*, helpers::*, math::*, referral::*, transfer::*, ID};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct FillBid<'info> {
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Box<Account<'info, Ido>>,
+    #[account(address = ido.acdm_mint)]
+    acdm_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = ido.usdc_mint)]
+    usdc_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = ido.usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    ido_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, seeds = [b"referral_usdc", ido.key().as_ref()], bump)]
+    referral_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"bid", ido.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = bid.bump,
+    )]
+    bid: Account<'info, Bid>,
+    #[account(
+        mut,
+        associated_token::authority = bid,
+        associated_token::mint = ido.usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    bid_usdc: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    seller: Signer<'info>,
+    #[account(mut)]
+    seller_acdm: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    seller_usdc: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"member", ido.key().as_ref(), seller.key().as_ref()],
+        bump = seller_member.bump,
+    )]
+    seller_member: Account<'info, Member>,
+    /// CHECK:
+    #[account(address = bid.authority)]
+    buyer: UncheckedAccount<'info>,
+    #[account(mut, token::authority = bid.authority, token::mint = ido.acdm_mint)]
+    buyer_acdm: InterfaceAccount<'info, TokenAccount>,
+    /// may alias `seller_member` or one of the seller's referers,
+    /// so it is reloaded before being updated and goes after `seller_member`
+    #[account(
+        mut,
+        seeds = [b"member", ido.key().as_ref(), buyer.key().as_ref()],
+        bump = buyer_member.bump,
+    )]
+    buyer_member: Account<'info, Member>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+    usdc_token_program: Interface<'info, TokenInterface>,
+}
+impl<'info> FillBid<'info> {
+    fn transfer_usdc_to_seller(&self, id: u64, amount: u64) -> Result<()> {
+        let ido = self.ido.key();
+        let signer: &[&[&[u8]]] = &[&[
+            b"bid".as_ref(),
+            ido.as_ref(),
+            &id.to_le_bytes(),
+            &[self.bid.bump],
+        ]];
+        let cpi_accounts = TransferChecked {
+            from: self.bid_usdc.to_account_info(),
+            mint: self.usdc_mint.to_account_info(),
+            to: self.seller_usdc.to_account_info(),
+            authority: self.bid.to_account_info(),
+        };
+        let cpi_program = self.usdc_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        transfer_checked(cpi_ctx, amount, self.usdc_mint.decimals)
+    }
+
+    fn transfer_acdm_to_buyer(&self, amount: u64) -> Result<u64> {
+        let cpi_accounts = TransferChecked {
+            from: self.seller_acdm.to_account_info(),
+            mint: self.acdm_mint.to_account_info(),
+            to: self.buyer_acdm.to_account_info(),
+            authority: self.seller.to_account_info(),
+        };
+        let cpi_program = self.acdm_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        transfer_checked_received(cpi_ctx, amount, self.acdm_mint.decimals)
+    }
+}
+
+/// the seller pays the same fees as the seller of a redeemed order,
+/// out of the USDC escrowed by the bid
+pub fn fill_bid<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FillBid<'info>>,
+    id: u64,
+    acdm_amount: u64,
+) -> Result<()> {
+    not_paused(&ctx.accounts.ido)?;
+    is_trade_round(&ctx.accounts.ido)?;
+
+    let filled_amount = add(ctx.accounts.bid.filled_amount, acdm_amount)?;
+    if filled_amount > ctx.accounts.bid.original_amount {
+        return err!(IdoError::BidAmountExceeded);
+    }
+    ctx.accounts.bid.filled_amount = filled_amount;
+
+    let usdc_amount_total = acdm_to_usdc(
+        acdm_amount,
+        ctx.accounts.bid.price,
+        ctx.accounts.ido.acdm_decimals,
+        Rounding::Down,
+    )?;
+    ctx.accounts.ido.usdc_traded = add(ctx.accounts.ido.usdc_traded, usdc_amount_total)?;
+
+    let usdc_amount_to_ido = bps_of(
+        usdc_amount_total,
+        ctx.accounts.ido.config.fees.trade_fee_bps,
+    )?;
+    let usdc_amount_to_seller = sub(usdc_amount_total, usdc_amount_to_ido)?;
+
+    let ido = ctx.accounts.ido.key();
+    let signer: &[&[&[u8]]] = &[&[
+        b"bid".as_ref(),
+        ido.as_ref(),
+        &id.to_le_bytes(),
+        &[ctx.accounts.bid.bump],
+    ]];
+    send_to_referers_and_ido(
+        usdc_amount_total,
+        usdc_amount_to_ido,
+        &ctx.accounts.ido.config.fees.trade_referer_bps,
+        ido,
+        &ctx.accounts.seller_member,
+        &ctx.accounts.usdc_mint,
+        &ctx.accounts.bid_usdc,
+        &ctx.accounts.bid.to_account_info(),
+        signer,
+        &ctx.accounts.ido_usdc,
+        &ctx.accounts.referral_usdc,
+        &ctx.accounts.usdc_token_program,
+        ctx.remaining_accounts,
+    )?;
+
+    ctx.accounts
+        .transfer_usdc_to_seller(id, usdc_amount_to_seller)?;
+
+    let acdm_amount_received = ctx.accounts.transfer_acdm_to_buyer(acdm_amount)?;
+
+    ctx.accounts.seller_member.acdm_sold = add(ctx.accounts.seller_member.acdm_sold, acdm_amount)?;
+    ctx.accounts.seller_member.exit(&ID)?;
+
+    ctx.accounts.buyer_member.reload()?;
+    ctx.accounts.buyer_member.acdm_bought =
+        add(ctx.accounts.buyer_member.acdm_bought, acdm_amount_received)?;
+
+    emit!(FillBidEvent {
+        id,
+        seller: ctx.accounts.seller.key(),
+        amount: acdm_amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct FillBidEvent {
+    id: u64,
+    seller: Pubkey,
+    amount: u64,
+}
@@ -1,34 +1,115 @@
-use crate::{account::*, config::*};
+use crate::{account::*, config::*, error::*, math::*};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, TokenAccount};
+use anchor_spl::token_interface::{
+    self, spl_token_2022::instruction::AuthorityType, Mint, SetAuthority, TokenAccount,
+    TokenInterface,
+};
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
-    #[account(init, payer = ido_authority, seeds = [b"ido"], bump, space = 8 + Ido::LEN)]
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"ido", acdm_mint.key().as_ref()],
+        bump,
+        space = 8 + Ido::LEN,
+    )]
     ido: Account<'info, Ido>,
     #[account(mut)]
-    ido_authority: Signer<'info>,
-    acdm_mint: Account<'info, Mint>,
-    #[account(associated_token::authority = ido, associated_token::mint = acdm_mint)]
-    ido_acdm: Account<'info, TokenAccount>,
-    usdc_mint: Account<'info, Mint>,
-    #[account(associated_token::authority = ido, associated_token::mint = usdc_mint)]
-    ido_usdc: Account<'info, TokenAccount>,
+    admin: Signer<'info>,
+    /// hands the minting of ACDM over to the IDO
+    acdm_mint_authority: Signer<'info>,
+    #[account(mut, mint::authority = acdm_mint_authority)]
+    acdm_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        associated_token::authority = ido,
+        associated_token::mint = acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    ido_acdm: InterfaceAccount<'info, TokenAccount>,
+    usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        associated_token::authority = ido,
+        associated_token::mint = usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    ido_usdc: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"referral_usdc", ido.key().as_ref()],
+        bump,
+        token::authority = ido,
+        token::mint = usdc_mint,
+        token::token_program = usdc_token_program,
+    )]
+    referral_usdc: InterfaceAccount<'info, TokenAccount>,
     rent: Sysvar<'info, Rent>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+    usdc_token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
 }
+impl<'info> Initialize<'info> {
+    fn transfer_mint_authority(&self) -> Result<()> {
+        let cpi_accounts = SetAuthority {
+            current_authority: self.acdm_mint_authority.to_account_info(),
+            account_or_mint: self.acdm_mint.to_account_info(),
+        };
+        let cpi_program = self.acdm_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::set_authority(cpi_ctx, AuthorityType::MintTokens, Some(self.ido.key()))
+    }
+}
+
+pub fn initialize(ctx: Context<Initialize>, round_time: i64, config: IdoConfig) -> Result<()> {
+    init_ido(
+        &mut ctx.accounts.ido,
+        ctx.bumps.ido,
+        ctx.accounts.admin.key(),
+        &ctx.accounts.acdm_mint,
+        &ctx.accounts.usdc_mint,
+        round_time,
+        config,
+    )?;
+
+    ctx.accounts.transfer_mint_authority()
+}
+
+/// shared by `initialize` and `initialize_with_mint`
+pub(crate) fn init_ido(
+    ido: &mut Account<Ido>,
+    bump: u8,
+    admin: Pubkey,
+    acdm_mint: &InterfaceAccount<Mint>,
+    usdc_mint: &InterfaceAccount<Mint>,
+    round_time: i64,
+    config: IdoConfig,
+) -> Result<()> {
+    config.validate()?;
+    if round_time < 0 {
+        return err!(IdoError::InvalidConfig);
+    }
+
+    let ts = Clock::get()?.unix_timestamp;
 
-pub fn initialize(ctx: Context<Initialize>, round_time: u32) -> Result<()> {
-    let ts = Clock::get()?.unix_timestamp as u32;
-
-    ctx.accounts.ido.bump = *ctx.bumps.get("ido").unwrap();
-    ctx.accounts.ido.authority = ctx.accounts.ido_authority.key();
-    ctx.accounts.ido.state = IdoState::NotStarted;
-    ctx.accounts.ido.acdm_mint = ctx.accounts.acdm_mint.key();
-    ctx.accounts.ido.usdc_mint = ctx.accounts.usdc_mint.key();
-    ctx.accounts.ido.usdc_traded = INITIAL_ISSUE * INITIAL_PRICE;
-    ctx.accounts.ido.round_time = round_time;
-    ctx.accounts.ido.current_state_start_ts = ts;
+    ido.version = Ido::VERSION;
+    ido.bump = bump;
+    ido.admin = admin;
+    ido.operator = admin;
+    ido.treasurer = admin;
+    ido.state = IdoState::NotStarted;
+    ido.acdm_mint = acdm_mint.key();
+    ido.usdc_mint = usdc_mint.key();
+    ido.acdm_decimals = acdm_mint.decimals;
+    ido.usdc_traded = acdm_to_usdc(
+        config.initial_issue,
+        config.initial_price,
+        acdm_mint.decimals,
+        Rounding::Down,
+    )?;
+    ido.round_time = round_time;
+    ido.current_state_start_ts = ts;
+    ido.config = config;
 
     emit!(InitializeEvent {});
 
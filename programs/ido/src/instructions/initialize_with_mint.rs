@@ -0,0 +1,78 @@
+use super::initialize::init_ido;
+use crate::{account::*, config::*};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+#[instruction(round_time: i64, config: IdoConfig, acdm_decimals: u8)]
+pub struct InitializeWithMint<'info> {
+    #[account(
+        init,
+        payer = admin,
+        mint::authority = ido,
+        mint::decimals = acdm_decimals,
+        mint::token_program = acdm_token_program,
+    )]
+    acdm_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"ido", acdm_mint.key().as_ref()],
+        bump,
+        space = 8 + Ido::LEN,
+    )]
+    ido: Account<'info, Ido>,
+    #[account(mut)]
+    admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        associated_token::authority = ido,
+        associated_token::mint = acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    ido_acdm: InterfaceAccount<'info, TokenAccount>,
+    usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        associated_token::authority = ido,
+        associated_token::mint = usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    ido_usdc: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"referral_usdc", ido.key().as_ref()],
+        bump,
+        token::authority = ido,
+        token::mint = usdc_mint,
+        token::token_program = usdc_token_program,
+    )]
+    referral_usdc: InterfaceAccount<'info, TokenAccount>,
+    rent: Sysvar<'info, Rent>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+    usdc_token_program: Interface<'info, TokenInterface>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    system_program: Program<'info, System>,
+}
+
+/// same as `initialize`, but creates the ACDM mint with the IDO as its mint authority
+pub fn initialize_with_mint(
+    ctx: Context<InitializeWithMint>,
+    round_time: i64,
+    config: IdoConfig,
+    _acdm_decimals: u8,
+) -> Result<()> {
+    init_ido(
+        &mut ctx.accounts.ido,
+        ctx.bumps.ido,
+        ctx.accounts.admin.key(),
+        &ctx.accounts.acdm_mint,
+        &ctx.accounts.usdc_mint,
+        round_time,
+        config,
+    )
+}
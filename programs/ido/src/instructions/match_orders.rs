@@ -0,0 +1,216 @@
+use crate::{account::*, error::*, helpers::*, math::*, referral::*, transfer::*, ID};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(order_id: u64, bid_id: u64)]
+pub struct MatchOrders<'info> {
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Box<Account<'info, Ido>>,
+    #[account(address = ido.acdm_mint)]
+    acdm_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = ido.usdc_mint)]
+    usdc_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = ido.usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    ido_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, seeds = [b"referral_usdc", ido.key().as_ref()], bump)]
+    referral_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"order", ido.key().as_ref(), order_id.to_le_bytes().as_ref()],
+        bump = order.bump,
+    )]
+    order: Box<Account<'info, Order>>,
+    #[account(
+        mut,
+        associated_token::authority = order,
+        associated_token::mint = ido.acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    order_acdm: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK:
+    #[account(address = order.authority)]
+    seller: UncheckedAccount<'info>,
+    #[account(mut, token::authority = order.authority, token::mint = ido.usdc_mint)]
+    seller_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"member", ido.key().as_ref(), seller.key().as_ref()],
+        bump = seller_member.bump,
+    )]
+    seller_member: Account<'info, Member>,
+    #[account(
+        mut,
+        seeds = [b"bid", ido.key().as_ref(), bid_id.to_le_bytes().as_ref()],
+        bump = bid.bump,
+    )]
+    bid: Box<Account<'info, Bid>>,
+    #[account(
+        mut,
+        associated_token::authority = bid,
+        associated_token::mint = ido.usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    bid_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK:
+    #[account(address = bid.authority)]
+    buyer: UncheckedAccount<'info>,
+    #[account(mut, token::authority = bid.authority, token::mint = ido.acdm_mint)]
+    buyer_acdm: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// may alias `seller_member` or one of the seller's referers,
+    /// so it is reloaded before being updated and goes after `seller_member`
+    #[account(
+        mut,
+        seeds = [b"member", ido.key().as_ref(), buyer.key().as_ref()],
+        bump = buyer_member.bump,
+    )]
+    buyer_member: Account<'info, Member>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+    usdc_token_program: Interface<'info, TokenInterface>,
+}
+impl<'info> MatchOrders<'info> {
+    fn transfer_usdc_to_seller(&self, bid_id: u64, amount: u64) -> Result<()> {
+        let ido = self.ido.key();
+        let signer: &[&[&[u8]]] = &[&[
+            b"bid".as_ref(),
+            ido.as_ref(),
+            &bid_id.to_le_bytes(),
+            &[self.bid.bump],
+        ]];
+        let cpi_accounts = TransferChecked {
+            from: self.bid_usdc.to_account_info(),
+            mint: self.usdc_mint.to_account_info(),
+            to: self.seller_usdc.to_account_info(),
+            authority: self.bid.to_account_info(),
+        };
+        let cpi_program = self.usdc_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        transfer_checked(cpi_ctx, amount, self.usdc_mint.decimals)
+    }
+
+    fn transfer_acdm_to_buyer(&self, order_id: u64, amount: u64) -> Result<u64> {
+        let ido = self.ido.key();
+        let signer: &[&[&[u8]]] = &[&[
+            b"order".as_ref(),
+            ido.as_ref(),
+            &order_id.to_le_bytes(),
+            &[self.order.bump],
+        ]];
+        let cpi_accounts = TransferChecked {
+            from: self.order_acdm.to_account_info(),
+            mint: self.acdm_mint.to_account_info(),
+            to: self.buyer_acdm.to_account_info(),
+            authority: self.order.to_account_info(),
+        };
+        let cpi_program = self.acdm_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        transfer_checked_received(cpi_ctx, amount, self.acdm_mint.decimals)
+    }
+}
+
+/// permissionless, matches as much as both sides allow at the price of
+/// the maker, which is the side resting at its price the longest as told by `sequence`,
+/// and charges the seller the same fees as `redeem_order`
+pub fn match_orders<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MatchOrders<'info>>,
+    order_id: u64,
+    bid_id: u64,
+) -> Result<()> {
+    let ts = Clock::get()?.unix_timestamp;
+
+    not_paused(&ctx.accounts.ido)?;
+    is_trade_round(&ctx.accounts.ido)?;
+    if ctx.accounts.order.is_expired(&ctx.accounts.ido, ts) {
+        return err!(IdoError::OrderExpired);
+    }
+    if ctx.accounts.bid.price < ctx.accounts.order.price {
+        return err!(IdoError::OrdersDontCross);
+    }
+
+    let price = if ctx.accounts.order.sequence < ctx.accounts.bid.sequence {
+        ctx.accounts.order.price
+    } else {
+        ctx.accounts.bid.price
+    };
+    let acdm_amount = ctx.accounts.order_acdm.amount.min(sub(
+        ctx.accounts.bid.original_amount,
+        ctx.accounts.bid.filled_amount,
+    )?);
+    if acdm_amount == 0 {
+        return err!(IdoError::NothingToMatch);
+    }
+
+    // rounded down like `fill_bid`, so the bid's escrow covers it
+    let usdc_amount_total = acdm_to_usdc(
+        acdm_amount,
+        price,
+        ctx.accounts.ido.acdm_decimals,
+        Rounding::Down,
+    )?;
+    ctx.accounts.ido.usdc_traded = add(ctx.accounts.ido.usdc_traded, usdc_amount_total)?;
+
+    let usdc_amount_to_ido = bps_of(
+        usdc_amount_total,
+        ctx.accounts.ido.config.fees.trade_fee_bps,
+    )?;
+    let usdc_amount_to_seller = sub(usdc_amount_total, usdc_amount_to_ido)?;
+
+    let ido = ctx.accounts.ido.key();
+    let signer: &[&[&[u8]]] = &[&[
+        b"bid".as_ref(),
+        ido.as_ref(),
+        &bid_id.to_le_bytes(),
+        &[ctx.accounts.bid.bump],
+    ]];
+    send_to_referers_and_ido(
+        usdc_amount_total,
+        usdc_amount_to_ido,
+        &ctx.accounts.ido.config.fees.trade_referer_bps,
+        ido,
+        &ctx.accounts.seller_member,
+        &ctx.accounts.usdc_mint,
+        &ctx.accounts.bid_usdc,
+        &ctx.accounts.bid.to_account_info(),
+        signer,
+        &ctx.accounts.ido_usdc,
+        &ctx.accounts.referral_usdc,
+        &ctx.accounts.usdc_token_program,
+        ctx.remaining_accounts,
+    )?;
+
+    ctx.accounts
+        .transfer_usdc_to_seller(bid_id, usdc_amount_to_seller)?;
+    let acdm_amount_received = ctx.accounts.transfer_acdm_to_buyer(order_id, acdm_amount)?;
+
+    ctx.accounts.order.filled_amount = add(ctx.accounts.order.filled_amount, acdm_amount)?;
+    ctx.accounts.bid.filled_amount = add(ctx.accounts.bid.filled_amount, acdm_amount)?;
+
+    ctx.accounts.seller_member.acdm_sold = add(ctx.accounts.seller_member.acdm_sold, acdm_amount)?;
+    ctx.accounts.seller_member.exit(&ID)?;
+
+    ctx.accounts.buyer_member.reload()?;
+    ctx.accounts.buyer_member.acdm_bought =
+        add(ctx.accounts.buyer_member.acdm_bought, acdm_amount_received)?;
+
+    emit!(MatchOrdersEvent {
+        order_id,
+        bid_id,
+        price,
+        amount: acdm_amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct MatchOrdersEvent {
+    order_id: u64,
+    bid_id: u64,
+    price: u64,
+    amount: u64,
+}
@@ -0,0 +1,185 @@
+use super::initialize::init_ido;
+use crate::{account::*, config::*, error::*, legacy::*, math::*, transfer::*, ID};
+use anchor_lang::{prelude::*, Discriminator};
+use anchor_spl::token_interface::{
+    self, spl_token_2022::instruction::AuthorityType, CloseAccount, Mint, SetAuthority,
+    TokenAccount, TokenInterface,
+};
+
+#[derive(Accounts)]
+pub struct MigrateIdo<'info> {
+    /// CHECK: deserialized in the handler, as its layout is outdated
+    #[account(mut, owner = ID, seeds = [b"ido"], bump)]
+    legacy_ido: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        associated_token::authority = legacy_ido,
+        associated_token::mint = acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    legacy_ido_acdm: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::authority = legacy_ido,
+        associated_token::mint = usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    legacy_ido_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"ido", acdm_mint.key().as_ref()],
+        bump,
+        space = 8 + Ido::LEN,
+    )]
+    ido: Box<Account<'info, Ido>>,
+    #[account(mut)]
+    admin: Signer<'info>,
+    /// hands the minting of ACDM over to the IDO
+    acdm_mint_authority: Signer<'info>,
+    #[account(mut, mint::authority = acdm_mint_authority)]
+    acdm_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    ido_acdm: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    usdc_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    ido_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"referral_usdc", ido.key().as_ref()],
+        bump,
+        token::authority = ido,
+        token::mint = usdc_mint,
+        token::token_program = usdc_token_program,
+    )]
+    referral_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    rent: Sysvar<'info, Rent>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+    usdc_token_program: Interface<'info, TokenInterface>,
+    system_program: Program<'info, System>,
+}
+impl<'info> MigrateIdo<'info> {
+    /// moves the whole balance of a token account of the legacy IDO to `to` and closes it
+    fn move_tokens(
+        &self,
+        from: &InterfaceAccount<'info, TokenAccount>,
+        to: &InterfaceAccount<'info, TokenAccount>,
+        mint: &InterfaceAccount<'info, Mint>,
+        token_program: &Interface<'info, TokenInterface>,
+        legacy_bump: u8,
+    ) -> Result<()> {
+        let signer: &[&[&[u8]]] = &[&[b"ido".as_ref(), &[legacy_bump]]];
+
+        if from.amount != 0 {
+            let cpi_accounts = TransferChecked {
+                from: from.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: self.legacy_ido.to_account_info(),
+            };
+            let cpi_program = token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            transfer_checked(cpi_ctx, from.amount, mint.decimals)?;
+        }
+
+        let cpi_accounts = CloseAccount {
+            account: from.to_account_info(),
+            destination: self.admin.to_account_info(),
+            authority: self.legacy_ido.to_account_info(),
+        };
+        let cpi_program = token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        close_account(cpi_ctx, &mint.to_account_info())
+    }
+
+    fn transfer_mint_authority(&self) -> Result<()> {
+        let cpi_accounts = SetAuthority {
+            current_authority: self.acdm_mint_authority.to_account_info(),
+            account_or_mint: self.acdm_mint.to_account_info(),
+        };
+        let cpi_program = self.acdm_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::set_authority(cpi_ctx, AuthorityType::MintTokens, Some(self.ido.key()))
+    }
+}
+
+/// moves the IDO of the first release to its current address, keeping its rounds and order ids,
+/// the config is new as the first release had it hardcoded
+pub fn migrate_ido(ctx: Context<MigrateIdo>, config: IdoConfig) -> Result<()> {
+    let legacy_info = ctx.accounts.legacy_ido.to_account_info();
+    let legacy: IdoV0 = match legacy_data_len(&legacy_info, Ido::discriminator())? {
+        IdoV0::LEN => load_legacy(&legacy_info)?,
+        _ => return err!(IdoError::AlreadyMigrated),
+    };
+
+    if legacy.authority != ctx.accounts.admin.key()
+        || legacy.acdm_mint != ctx.accounts.acdm_mint.key()
+        || legacy.usdc_mint != ctx.accounts.usdc_mint.key()
+    {
+        return err!(ErrorCode::ConstraintAddress);
+    }
+
+    init_ido(
+        &mut ctx.accounts.ido,
+        ctx.bumps.ido,
+        legacy.authority,
+        &ctx.accounts.acdm_mint,
+        &ctx.accounts.usdc_mint,
+        legacy.round_time as i64,
+        config,
+    )?;
+
+    let ido = &mut ctx.accounts.ido;
+    ido.state = legacy.state;
+    ido.acdm_price = legacy_price_to_current(legacy.acdm_price, ido.acdm_decimals)?;
+    ido.usdc_traded = legacy.usdc_traded;
+    ido.orders = legacy.orders;
+    ido.current_state_start_ts = legacy.current_state_start_ts as i64;
+    ido.sale_rounds_started = legacy.sale_rounds_started;
+    ido.migrated = true;
+
+    let accounts = &ctx.accounts;
+    accounts.move_tokens(
+        &accounts.legacy_ido_acdm,
+        &accounts.ido_acdm,
+        &accounts.acdm_mint,
+        &accounts.acdm_token_program,
+        legacy.bump,
+    )?;
+    accounts.move_tokens(
+        &accounts.legacy_ido_usdc,
+        &accounts.ido_usdc,
+        &accounts.usdc_mint,
+        &accounts.usdc_token_program,
+        legacy.bump,
+    )?;
+    accounts.transfer_mint_authority()?;
+
+    close_legacy(
+        &accounts.legacy_ido.to_account_info(),
+        &accounts.admin.to_account_info(),
+    )?;
+
+    emit!(MigrateIdoEvent {
+        version: Ido::VERSION,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct MigrateIdoEvent {
+    version: u8,
+}
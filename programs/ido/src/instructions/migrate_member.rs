@@ -0,0 +1,64 @@
+use crate::{account::*, error::*, legacy::*, ID};
+use anchor_lang::{prelude::*, Discriminator};
+
+#[derive(Accounts)]
+pub struct MigrateMember<'info> {
+    #[account(
+        seeds = [b"ido", ido.acdm_mint.as_ref()],
+        bump = ido.bump,
+        constraint = ido.migrated @ IdoError::NotMigrated,
+    )]
+    ido: Account<'info, Ido>,
+    /// CHECK: only used to derive the member's addresses
+    authority: UncheckedAccount<'info>,
+    /// CHECK: deserialized in the handler, as its layout is outdated
+    #[account(mut, owner = ID, seeds = [b"member", authority.key().as_ref()], bump)]
+    legacy_member: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"member", ido.key().as_ref(), authority.key().as_ref()],
+        bump,
+        space = 8 + Member::LEN,
+    )]
+    member: Account<'info, Member>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+/// moves a member of the first release to its current address, anyone can pay for it
+/// and gets the rent of the legacy account back
+pub fn migrate_member(ctx: Context<MigrateMember>) -> Result<()> {
+    let legacy_info = ctx.accounts.legacy_member.to_account_info();
+    let legacy: MemberV0 = match legacy_data_len(&legacy_info, Member::discriminator())? {
+        MemberV0::LEN => load_legacy(&legacy_info)?,
+        _ => return err!(IdoError::AlreadyMigrated),
+    };
+
+    // statistics weren't tracked by the first release, so they start from zero,
+    // and it let members refer themselves, which would now form a cycle
+    let authority = ctx.accounts.authority.key();
+    let member = &mut ctx.accounts.member;
+    member.version = Member::VERSION;
+    member.bump = ctx.bumps.member;
+    member.referer = legacy.referer.filter(|&referer| referer != authority);
+
+    close_legacy(
+        &ctx.accounts.legacy_member.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+    )?;
+
+    emit!(MigrateMemberEvent {
+        authority,
+        version: Member::VERSION,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct MigrateMemberEvent {
+    authority: Pubkey,
+    version: u8,
+}
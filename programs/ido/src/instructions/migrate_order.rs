@@ -0,0 +1,125 @@
+use crate::{account::*, error::*, legacy::*, math::*, transfer::*, ID};
+use anchor_lang::{prelude::*, Discriminator};
+use anchor_spl::token_interface::{CloseAccount, Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct MigrateOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"ido", ido.acdm_mint.as_ref()],
+        bump = ido.bump,
+        constraint = ido.migrated @ IdoError::NotMigrated,
+    )]
+    ido: Account<'info, Ido>,
+    /// CHECK: deserialized in the handler, as its layout is outdated
+    #[account(mut, owner = ID, seeds = [b"order", id.to_le_bytes().as_ref()], bump)]
+    legacy_order: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        associated_token::authority = legacy_order,
+        associated_token::mint = acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    legacy_order_acdm: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"order", ido.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Order::LEN,
+    )]
+    order: Account<'info, Order>,
+    #[account(mut, address = ido.acdm_mint)]
+    acdm_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::authority = order,
+        associated_token::mint = acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    order_acdm: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+    system_program: Program<'info, System>,
+}
+impl<'info> MigrateOrder<'info> {
+    /// returns the amount the order's new escrow received
+    fn move_acdm(&self, id: u64, legacy_bump: u8) -> Result<u64> {
+        let signer: &[&[&[u8]]] = &[&[b"order".as_ref(), &id.to_le_bytes(), &[legacy_bump]]];
+        let amount = self.legacy_order_acdm.amount;
+
+        let received = if amount != 0 {
+            let cpi_accounts = TransferChecked {
+                from: self.legacy_order_acdm.to_account_info(),
+                mint: self.acdm_mint.to_account_info(),
+                to: self.order_acdm.to_account_info(),
+                authority: self.legacy_order.to_account_info(),
+            };
+            let cpi_program = self.acdm_token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            transfer_checked_received(cpi_ctx, amount, self.acdm_mint.decimals)?
+        } else {
+            0
+        };
+
+        let cpi_accounts = CloseAccount {
+            account: self.legacy_order_acdm.to_account_info(),
+            destination: self.payer.to_account_info(),
+            authority: self.legacy_order.to_account_info(),
+        };
+        let cpi_program = self.acdm_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        close_account(cpi_ctx, &self.acdm_mint.to_account_info())?;
+
+        Ok(received)
+    }
+}
+
+/// moves an order of the first release to its current address, anyone can pay for it
+/// and gets the rent of the legacy accounts back
+pub fn migrate_order(ctx: Context<MigrateOrder>, id: u64) -> Result<()> {
+    let ts = Clock::get()?.unix_timestamp;
+
+    let legacy_info = ctx.accounts.legacy_order.to_account_info();
+    let legacy: OrderV0 = match legacy_data_len(&legacy_info, Order::discriminator())? {
+        OrderV0::LEN => load_legacy(&legacy_info)?,
+        _ => return err!(IdoError::AlreadyMigrated),
+    };
+
+    let acdm_amount = ctx.accounts.move_acdm(id, legacy.bump)?;
+
+    // fills and the creation time weren't tracked by the first release,
+    // so the order counts as added at migration with what is left of it
+    let sequence = ctx.accounts.ido.next_sequence()?;
+    let order = &mut ctx.accounts.order;
+    order.version = Order::VERSION;
+    order.bump = ctx.bumps.order;
+    order.authority = legacy.authority;
+    order.price = legacy_price_to_current(legacy.price, ctx.accounts.ido.acdm_decimals)?;
+    order.original_amount = acdm_amount;
+    order.filled_amount = 0;
+    order.created_ts = ts;
+    order.round = ctx.accounts.ido.sale_rounds_started;
+    order.expiry = OrderExpiry::Never;
+    order.sequence = sequence;
+
+    close_legacy(
+        &ctx.accounts.legacy_order.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+    )?;
+
+    emit!(MigrateOrderEvent {
+        id,
+        version: Order::VERSION,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct MigrateOrderEvent {
+    id: u64,
+    version: u8,
+}
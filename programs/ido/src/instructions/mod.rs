@@ -1,15 +1,41 @@
 pub use self::{
-    add_order::*, buy_acdm::*, end_ido::*, initialize::*, redeem_order::*, register_member::*,
-    remove_order::*, start_sale_round::*, start_trade_round::*, withdraw_ido_usdc::*,
+    accept_authority::*, add_bid::*, add_order::*, buy_acdm::*, claim_referral_rewards::*,
+    close_expired_order::*, crank::*, create_referral_code::*, end_ido::*, fill_bid::*,
+    initialize::*, initialize_with_mint::*, match_orders::*, migrate_ido::*, migrate_member::*,
+    migrate_order::*, pause::*, propose_authority::*, redeem_order::*, redeem_orders::*,
+    register_member::*, register_member_with_code::*, remove_bid::*, remove_order::*, set_roles::*,
+    start_sale_round::*, start_trade_round::*, unpause::*, update_config::*, update_order::*,
+    withdraw_ido_usdc::*,
 };
 
+mod accept_authority;
+mod add_bid;
 mod add_order;
 mod buy_acdm;
+mod claim_referral_rewards;
+mod close_expired_order;
+mod crank;
+mod create_referral_code;
 mod end_ido;
+mod fill_bid;
 mod initialize;
+mod initialize_with_mint;
+mod match_orders;
+mod migrate_ido;
+mod migrate_member;
+mod migrate_order;
+mod pause;
+mod propose_authority;
 mod redeem_order;
+mod redeem_orders;
 mod register_member;
+mod register_member_with_code;
+mod remove_bid;
 mod remove_order;
+mod set_roles;
 mod start_sale_round;
 mod start_trade_round;
+mod unpause;
+mod update_config;
+mod update_order;
 mod withdraw_ido_usdc;
@@ -0,0 +1,26 @@
+use crate::{account::*, helpers::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(address = ido.admin)]
+    admin: Signer<'info>,
+}
+
+pub fn pause(ctx: Context<Pause>) -> Result<()> {
+    let ts = Clock::get()?.unix_timestamp;
+
+    not_paused(&ctx.accounts.ido)?;
+
+    ctx.accounts.ido.paused = true;
+    ctx.accounts.ido.paused_at = ts;
+
+    emit!(PauseEvent {});
+
+    Ok(())
+}
+
+#[event]
+struct PauseEvent {}
@@ -0,0 +1,24 @@
+use crate::account::*;
+use anchor_lang::prelude::*;
+
+/// the authority being transferred is the admin role
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(address = ido.admin)]
+    admin: Signer<'info>,
+}
+
+pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    ctx.accounts.ido.pending_authority = Some(new_authority);
+
+    emit!(ProposeAuthorityEvent { new_authority });
+
+    Ok(())
+}
+
+#[event]
+struct ProposeAuthorityEvent {
+    new_authority: Pubkey,
+}
@@ -1,93 +1,160 @@
-use crate::{account::*, error::*, helpers::*, referral::*};
+use crate::{account::*, error::*, helpers::*, math::*, referral::*, transfer::*, ID};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 #[instruction(id: u64)]
 pub struct RedeemOrder<'info> {
-    #[account(mut, seeds = [b"ido"], bump = ido.bump)]
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
     ido: Box<Account<'info, Ido>>,
-    #[account(mut, associated_token::authority = ido, associated_token::mint = ido.usdc_mint)]
-    ido_usdc: Box<Account<'info, TokenAccount>>,
-    #[account(mut, seeds = [b"order", id.to_le_bytes().as_ref()], bump = order.bump)]
+    #[account(address = ido.acdm_mint)]
+    acdm_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = ido.usdc_mint)]
+    usdc_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = ido.usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    ido_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, seeds = [b"referral_usdc", ido.key().as_ref()], bump)]
+    referral_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"order", ido.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = order.bump,
+    )]
     order: Account<'info, Order>,
-    #[account(mut, associated_token::authority = order, associated_token::mint = ido.acdm_mint)]
-    order_acdm: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::authority = order,
+        associated_token::mint = ido.acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    order_acdm: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     buyer: Signer<'info>,
     #[account(mut)]
-    buyer_acdm: Account<'info, TokenAccount>,
+    buyer_acdm: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    buyer_usdc: Account<'info, TokenAccount>,
+    buyer_usdc: InterfaceAccount<'info, TokenAccount>,
     /// CHECK:
     #[account(address = order.authority)]
     seller: UncheckedAccount<'info>,
-    #[account(seeds = [b"member", seller.key().as_ref()], bump = seller_member.bump)]
+    #[account(
+        mut,
+        seeds = [b"member", ido.key().as_ref(), seller.key().as_ref()],
+        bump = seller_member.bump,
+    )]
     seller_member: Account<'info, Member>,
     #[account(mut, token::authority = order.authority)]
-    seller_usdc: Account<'info, TokenAccount>,
-    token_program: Program<'info, Token>,
+    seller_usdc: InterfaceAccount<'info, TokenAccount>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+    usdc_token_program: Interface<'info, TokenInterface>,
 }
 impl<'info> RedeemOrder<'info> {
     fn transfer_usdc_to_seller(&self, amount: u64) -> Result<()> {
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: self.buyer_usdc.to_account_info(),
+            mint: self.usdc_mint.to_account_info(),
             to: self.seller_usdc.to_account_info(),
             authority: self.buyer.to_account_info(),
         };
-        let cpi_program = self.token_program.to_account_info();
+        let cpi_program = self.usdc_token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, amount)
+        transfer_checked(cpi_ctx, amount, self.usdc_mint.decimals)
     }
 
-    fn transfer_acdm_to_buyer(&self, id: u64, amount: u64) -> Result<()> {
-        let signer: &[&[&[u8]]] = &[&[b"order".as_ref(), &id.to_le_bytes(), &[self.order.bump]]];
-        let cpi_accounts = Transfer {
+    fn transfer_acdm_to_buyer(&self, id: u64, amount: u64) -> Result<u64> {
+        let ido = self.ido.key();
+        let signer: &[&[&[u8]]] = &[&[
+            b"order".as_ref(),
+            ido.as_ref(),
+            &id.to_le_bytes(),
+            &[self.order.bump],
+        ]];
+        let cpi_accounts = TransferChecked {
             from: self.order_acdm.to_account_info(),
+            mint: self.acdm_mint.to_account_info(),
             to: self.buyer_acdm.to_account_info(),
             authority: self.order.to_account_info(),
         };
-        let cpi_program = self.token_program.to_account_info();
+        let cpi_program = self.acdm_token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-        token::transfer(cpi_ctx, amount)
+        transfer_checked_received(cpi_ctx, amount, self.acdm_mint.decimals)
     }
 }
 
+/// `remaining_accounts` are the member accounts of the seller's referers,
+/// optionally followed by the buyer's member account to keep their stats,
+/// as buyers don't have to be members
 pub fn redeem_order<'info>(
-    ctx: Context<'_, '_, '_, 'info, RedeemOrder<'info>>,
+    ctx: Context<'_, '_, 'info, 'info, RedeemOrder<'info>>,
     id: u64,
     acdm_amount: u64,
 ) -> Result<()> {
+    let ts = Clock::get()?.unix_timestamp;
+
+    not_paused(&ctx.accounts.ido)?;
     is_trade_round(&ctx.accounts.ido)?;
+    if ctx.accounts.order.is_expired(&ctx.accounts.ido, ts) {
+        return err!(IdoError::OrderExpired);
+    }
 
-    let usdc_amount_total = acdm_amount
-        .checked_mul(ctx.accounts.order.price)
-        .ok_or(IdoError::Overflow)?;
-    ctx.accounts.ido.usdc_traded = (ctx.accounts.ido.usdc_traded)
-        .checked_add(usdc_amount_total)
-        .ok_or(IdoError::Overflow)?;
+    let usdc_amount_total = acdm_to_usdc(
+        acdm_amount,
+        ctx.accounts.order.price,
+        ctx.accounts.ido.acdm_decimals,
+        Rounding::Up,
+    )?;
+    ctx.accounts.ido.usdc_traded = add(ctx.accounts.ido.usdc_traded, usdc_amount_total)?;
 
-    let usdc_amount_to_ido = usdc_amount_total / 20; // 5%
-    let usdc_amount_to_referer = usdc_amount_to_ido / 2; // 2.5%
-    let usdc_amount_to_referer2 = usdc_amount_to_ido - usdc_amount_to_referer; // 2.5%
-    let usdc_amount_so_seller = usdc_amount_total - usdc_amount_to_ido; // 95%
+    let usdc_amount_to_ido = bps_of(
+        usdc_amount_total,
+        ctx.accounts.ido.config.fees.trade_fee_bps,
+    )?;
+    let usdc_amount_so_seller = sub(usdc_amount_total, usdc_amount_to_ido)?;
 
-    send_to_referers_and_ido(
+    let referers = send_to_referers_and_ido(
+        usdc_amount_total,
         usdc_amount_to_ido,
-        usdc_amount_to_referer,
-        usdc_amount_to_referer2,
+        &ctx.accounts.ido.config.fees.trade_referer_bps,
+        ctx.accounts.ido.key(),
         &ctx.accounts.seller_member,
-        &ctx.accounts.buyer,
+        &ctx.accounts.usdc_mint,
         &ctx.accounts.buyer_usdc,
+        &ctx.accounts.buyer.to_account_info(),
+        &[],
         &ctx.accounts.ido_usdc,
-        &ctx.accounts.token_program,
+        &ctx.accounts.referral_usdc,
+        &ctx.accounts.usdc_token_program,
         ctx.remaining_accounts,
     )?;
 
     ctx.accounts
         .transfer_usdc_to_seller(usdc_amount_so_seller)?;
 
-    ctx.accounts.transfer_acdm_to_buyer(id, acdm_amount)?;
+    let acdm_amount_received = ctx.accounts.transfer_acdm_to_buyer(id, acdm_amount)?;
+    ctx.accounts.order.filled_amount = add(ctx.accounts.order.filled_amount, acdm_amount)?;
+
+    ctx.accounts.seller_member.acdm_sold = add(ctx.accounts.seller_member.acdm_sold, acdm_amount)?;
+    ctx.accounts.seller_member.exit(&ID)?;
+
+    // loaded only now, as it may alias `seller_member` or one of the seller's referers
+    if let Some(buyer_member) = ctx.remaining_accounts.get(referers) {
+        let mut buyer_member = get_member(
+            buyer_member,
+            ctx.accounts.ido.key(),
+            ctx.accounts.buyer.key(),
+        )?;
+        buyer_member.acdm_bought = add(buyer_member.acdm_bought, acdm_amount_received)?;
+        buyer_member.exit(&ID)?;
+
+        // `seller_member` is written again when the instruction exits,
+        // so it must not keep a stale copy of the buyer's member account
+        ctx.accounts.seller_member.reload()?;
+    }
 
     emit!(RedeemOrderEvent {
         id,
@@ -0,0 +1,290 @@
+use crate::{account::*, error::*, helpers::*, math::*, referral::*, transfer::*, ID};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct RedeemOrders<'info> {
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Box<Account<'info, Ido>>,
+    #[account(address = ido.acdm_mint)]
+    acdm_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = ido.usdc_mint)]
+    usdc_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = ido.usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    ido_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, seeds = [b"referral_usdc", ido.key().as_ref()], bump)]
+    referral_usdc: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    buyer: Signer<'info>,
+    #[account(mut)]
+    buyer_acdm: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    buyer_usdc: InterfaceAccount<'info, TokenAccount>,
+    /// may alias the member account of a seller or one of their referers,
+    /// so it is reloaded before being updated
+    #[account(
+        mut,
+        seeds = [b"member", ido.key().as_ref(), buyer.key().as_ref()],
+        bump = buyer_member.bump,
+    )]
+    buyer_member: Account<'info, Member>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+    usdc_token_program: Interface<'info, TokenInterface>,
+}
+impl<'info> RedeemOrders<'info> {
+    fn transfer_usdc_to_seller(
+        &self,
+        seller_usdc: &InterfaceAccount<'info, TokenAccount>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.buyer_usdc.to_account_info(),
+            mint: self.usdc_mint.to_account_info(),
+            to: seller_usdc.to_account_info(),
+            authority: self.buyer.to_account_info(),
+        };
+        let cpi_program = self.usdc_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        transfer_checked(cpi_ctx, amount, self.usdc_mint.decimals)
+    }
+
+    fn transfer_acdm_to_buyer(
+        &self,
+        order: &Account<'info, Order>,
+        order_acdm: &InterfaceAccount<'info, TokenAccount>,
+        id: u64,
+        amount: u64,
+    ) -> Result<u64> {
+        let ido = self.ido.key();
+        let signer: &[&[&[u8]]] = &[&[
+            b"order".as_ref(),
+            ido.as_ref(),
+            &id.to_le_bytes(),
+            &[order.bump],
+        ]];
+        let cpi_accounts = TransferChecked {
+            from: order_acdm.to_account_info(),
+            mint: self.acdm_mint.to_account_info(),
+            to: self.buyer_acdm.to_account_info(),
+            authority: order.to_account_info(),
+        };
+        let cpi_program = self.acdm_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        transfer_checked_received(cpi_ctx, amount, self.acdm_mint.decimals)
+    }
+}
+
+/// the order with each id in `ids` is expected in `remaining_accounts` as
+/// `order, order_acdm, seller, seller_member, seller_usdc`
+/// followed by the member accounts of the seller's referers, like for `redeem_order`
+struct OrderAccounts<'info> {
+    order: Account<'info, Order>,
+    order_acdm: InterfaceAccount<'info, TokenAccount>,
+    seller_member: Account<'info, Member>,
+    seller_usdc: InterfaceAccount<'info, TokenAccount>,
+}
+
+fn load_order_accounts<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    ido: &Ido,
+    ido_key: Pubkey,
+    acdm_token_program: &Pubkey,
+    id: u64,
+) -> Result<OrderAccounts<'info>> {
+    if accounts.len() < 5 {
+        return err!(ErrorCode::AccountNotEnoughKeys);
+    }
+
+    let order = Account::<Order>::try_from(&accounts[0])?;
+    let order_key = Pubkey::create_program_address(
+        &[b"order", ido_key.as_ref(), &id.to_le_bytes(), &[order.bump]],
+        &ID,
+    )
+    .map_err(|_| ErrorCode::ConstraintSeeds)?;
+    if order.key() != order_key {
+        return err!(ErrorCode::ConstraintSeeds);
+    }
+
+    let order_acdm = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?;
+    if order_acdm.key()
+        != get_associated_token_address_with_program_id(
+            &order_key,
+            &ido.acdm_mint,
+            acdm_token_program,
+        )
+    {
+        return err!(ErrorCode::ConstraintAssociated);
+    }
+
+    let seller = &accounts[2];
+    if seller.key() != order.authority {
+        return err!(ErrorCode::ConstraintAddress);
+    }
+    let seller_member = get_referer_member(accounts.get(3), ido_key, order.authority)?;
+
+    let seller_usdc = InterfaceAccount::<TokenAccount>::try_from(&accounts[4])?;
+    if seller_usdc.owner != order.authority {
+        return err!(ErrorCode::ConstraintTokenOwner);
+    }
+    if seller_usdc.mint != ido.usdc_mint {
+        return err!(ErrorCode::ConstraintTokenMint);
+    }
+
+    Ok(OrderAccounts {
+        order,
+        order_acdm,
+        seller_member,
+        seller_usdc,
+    })
+}
+
+/// fills the orders with `ids`, which must be sorted by price, until `acdm_amount` is bought,
+/// failing if the buyer would pay more than `max_average_price` per whole ACDM on average
+pub fn redeem_orders<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RedeemOrders<'info>>,
+    ids: Vec<u64>,
+    acdm_amount: u64,
+    max_average_price: u64,
+) -> Result<()> {
+    let ts = Clock::get()?.unix_timestamp;
+
+    not_paused(&ctx.accounts.ido)?;
+    is_trade_round(&ctx.accounts.ido)?;
+
+    let ido_key = ctx.accounts.ido.key();
+    let mut acdm_amount_filled = 0;
+    let mut acdm_amount_received = 0;
+    let mut usdc_amount_paid = 0;
+    let mut last_price = 0;
+    let mut next_account = 0;
+
+    for id in ids {
+        if acdm_amount_filled == acdm_amount {
+            break;
+        }
+
+        let mut accounts = load_order_accounts(
+            ctx.remaining_accounts
+                .get(next_account..)
+                .unwrap_or_default(),
+            &ctx.accounts.ido,
+            ido_key,
+            ctx.accounts.acdm_token_program.key,
+            id,
+        )?;
+        next_account += 5;
+
+        if accounts.order.price < last_price {
+            return err!(IdoError::OrdersNotSorted);
+        }
+        last_price = accounts.order.price;
+        if accounts.order.is_expired(&ctx.accounts.ido, ts) {
+            return err!(IdoError::OrderExpired);
+        }
+
+        let acdm_amount_to_fill =
+            sub(acdm_amount, acdm_amount_filled)?.min(accounts.order_acdm.amount);
+        let usdc_amount_total = acdm_to_usdc(
+            acdm_amount_to_fill,
+            accounts.order.price,
+            ctx.accounts.ido.acdm_decimals,
+            Rounding::Up,
+        )?;
+        let usdc_amount_to_ido = bps_of(
+            usdc_amount_total,
+            ctx.accounts.ido.config.fees.trade_fee_bps,
+        )?;
+        let usdc_amount_to_seller = sub(usdc_amount_total, usdc_amount_to_ido)?;
+
+        next_account += send_to_referers_and_ido(
+            usdc_amount_total,
+            usdc_amount_to_ido,
+            &ctx.accounts.ido.config.fees.trade_referer_bps,
+            ido_key,
+            &accounts.seller_member,
+            &ctx.accounts.usdc_mint,
+            &ctx.accounts.buyer_usdc,
+            &ctx.accounts.buyer.to_account_info(),
+            &[],
+            &ctx.accounts.ido_usdc,
+            &ctx.accounts.referral_usdc,
+            &ctx.accounts.usdc_token_program,
+            ctx.remaining_accounts
+                .get(next_account..)
+                .unwrap_or_default(),
+        )?;
+
+        ctx.accounts
+            .transfer_usdc_to_seller(&accounts.seller_usdc, usdc_amount_to_seller)?;
+        let acdm_amount_to_buyer = ctx.accounts.transfer_acdm_to_buyer(
+            &accounts.order,
+            &accounts.order_acdm,
+            id,
+            acdm_amount_to_fill,
+        )?;
+
+        accounts.order.filled_amount = add(accounts.order.filled_amount, acdm_amount_to_fill)?;
+        accounts.order.exit(&ID)?;
+        accounts.seller_member.acdm_sold =
+            add(accounts.seller_member.acdm_sold, acdm_amount_to_fill)?;
+        accounts.seller_member.exit(&ID)?;
+
+        acdm_amount_filled = add(acdm_amount_filled, acdm_amount_to_fill)?;
+        acdm_amount_received = add(acdm_amount_received, acdm_amount_to_buyer)?;
+        usdc_amount_paid = add(usdc_amount_paid, usdc_amount_total)?;
+
+        emit!(RedeemOrdersFillEvent {
+            id,
+            buyer: ctx.accounts.buyer.key(),
+            amount: acdm_amount_to_fill,
+            price: accounts.order.price,
+        });
+    }
+
+    let usdc_amount_limit = acdm_to_usdc(
+        acdm_amount_filled,
+        max_average_price,
+        ctx.accounts.ido.acdm_decimals,
+        Rounding::Up,
+    )?;
+    if usdc_amount_paid > usdc_amount_limit {
+        return err!(IdoError::PriceLimitExceeded);
+    }
+
+    ctx.accounts.ido.usdc_traded = add(ctx.accounts.ido.usdc_traded, usdc_amount_paid)?;
+
+    ctx.accounts.buyer_member.reload()?;
+    ctx.accounts.buyer_member.acdm_bought =
+        add(ctx.accounts.buyer_member.acdm_bought, acdm_amount_received)?;
+
+    emit!(RedeemOrdersEvent {
+        buyer: ctx.accounts.buyer.key(),
+        amount: acdm_amount_filled,
+        usdc_amount: usdc_amount_paid,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct RedeemOrdersFillEvent {
+    id: u64,
+    buyer: Pubkey,
+    amount: u64,
+    price: u64,
+}
+
+#[event]
+struct RedeemOrdersEvent {
+    buyer: Pubkey,
+    amount: u64,
+    usdc_amount: u64,
+}
@@ -3,10 +3,12 @@ use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct RegisterMember<'info> {
+    #[account(seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
     #[account(
         init,
         payer = authority,
-        seeds = [b"member", authority.key().as_ref()],
+        seeds = [b"member", ido.key().as_ref(), authority.key().as_ref()],
         bump,
         space = 8 + Member::LEN,
     )]
@@ -16,14 +18,23 @@ pub struct RegisterMember<'info> {
     system_program: Program<'info, System>,
 }
 
-pub fn register_member(ctx: Context<RegisterMember>, referer: Option<Pubkey>) -> Result<()> {
-    ctx.accounts.member.bump = *ctx.bumps.get("member").unwrap();
-    ctx.accounts.member.referer = referer;
-
+pub fn register_member<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RegisterMember<'info>>,
+    referer: Option<Pubkey>,
+) -> Result<()> {
     if let Some(referer) = referer {
-        get_referer_member(ctx.remaining_accounts, referer)?;
+        add_referee(
+            ctx.remaining_accounts,
+            &ctx.accounts.ido,
+            ctx.accounts.authority.key(),
+            referer,
+        )?;
     }
 
+    ctx.accounts.member.version = Member::VERSION;
+    ctx.accounts.member.bump = ctx.bumps.member;
+    ctx.accounts.member.referer = referer;
+
     emit!(RegisterMemberEvent {
         authority: ctx.accounts.authority.key(),
     });
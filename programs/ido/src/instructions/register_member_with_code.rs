@@ -0,0 +1,56 @@
+use crate::{account::*, referral::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(code: String)]
+pub struct RegisterMemberWithCode<'info> {
+    #[account(seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(
+        seeds = [b"code", ido.key().as_ref(), ReferralCode::seed(&code).as_ref()],
+        bump = referral_code.bump,
+    )]
+    referral_code: Account<'info, ReferralCode>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"member", ido.key().as_ref(), authority.key().as_ref()],
+        bump,
+        space = 8 + Member::LEN,
+    )]
+    member: Account<'info, Member>,
+    #[account(mut)]
+    authority: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+pub fn register_member_with_code<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RegisterMemberWithCode<'info>>,
+    code: String,
+) -> Result<()> {
+    let referer = ctx.accounts.referral_code.authority;
+
+    add_referee(
+        ctx.remaining_accounts,
+        &ctx.accounts.ido,
+        ctx.accounts.authority.key(),
+        referer,
+    )?;
+
+    ctx.accounts.member.version = Member::VERSION;
+    ctx.accounts.member.bump = ctx.bumps.member;
+    ctx.accounts.member.referer = Some(referer);
+
+    emit!(RegisterMemberWithCodeEvent {
+        authority: ctx.accounts.authority.key(),
+        code,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct RegisterMemberWithCodeEvent {
+    authority: Pubkey,
+    code: String,
+}
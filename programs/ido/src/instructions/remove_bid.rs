@@ -0,0 +1,89 @@
+use crate::{account::*, transfer::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{CloseAccount, Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct RemoveBid<'info> {
+    #[account(seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(
+        mut,
+        seeds = [b"bid", ido.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = bid.bump,
+        close = buyer,
+    )]
+    bid: Account<'info, Bid>,
+    #[account(mut, address = ido.usdc_mint)]
+    usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::authority = bid,
+        associated_token::mint = ido.usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    bid_usdc: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = bid.authority)]
+    buyer: Signer<'info>,
+    #[account(mut)]
+    buyer_usdc: InterfaceAccount<'info, TokenAccount>,
+    usdc_token_program: Interface<'info, TokenInterface>,
+}
+impl<'info> RemoveBid<'info> {
+    fn send_leftover_to_buyer(&self, id: u64) -> Result<()> {
+        let amount = self.bid_usdc.amount;
+
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let ido = self.ido.key();
+        let signer: &[&[&[u8]]] = &[&[
+            b"bid".as_ref(),
+            ido.as_ref(),
+            &id.to_le_bytes(),
+            &[self.bid.bump],
+        ]];
+        let cpi_accounts = TransferChecked {
+            from: self.bid_usdc.to_account_info(),
+            mint: self.usdc_mint.to_account_info(),
+            to: self.buyer_usdc.to_account_info(),
+            authority: self.bid.to_account_info(),
+        };
+        let cpi_program = self.usdc_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        transfer_checked(cpi_ctx, amount, self.usdc_mint.decimals)
+    }
+
+    fn close_bid_usdc_account(&self, id: u64) -> Result<()> {
+        let ido = self.ido.key();
+        let signer: &[&[&[u8]]] = &[&[
+            b"bid".as_ref(),
+            ido.as_ref(),
+            &id.to_le_bytes(),
+            &[self.bid.bump],
+        ]];
+        let cpi_accounts = CloseAccount {
+            account: self.bid_usdc.to_account_info(),
+            destination: self.buyer.to_account_info(),
+            authority: self.bid.to_account_info(),
+        };
+        let cpi_program = self.usdc_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        close_account(cpi_ctx, &self.usdc_mint.to_account_info())
+    }
+}
+
+pub fn remove_bid(ctx: Context<RemoveBid>, id: u64) -> Result<()> {
+    ctx.accounts.send_leftover_to_buyer(id)?;
+    ctx.accounts.close_bid_usdc_account(id)?;
+
+    emit!(RemoveBidEvent { id });
+
+    Ok(())
+}
+
+#[event]
+struct RemoveBidEvent {
+    id: u64,
+}
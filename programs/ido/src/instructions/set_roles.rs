@@ -0,0 +1,28 @@
+use crate::account::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetRoles<'info> {
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(address = ido.admin)]
+    admin: Signer<'info>,
+}
+
+pub fn set_roles(ctx: Context<SetRoles>, operator: Pubkey, treasurer: Pubkey) -> Result<()> {
+    ctx.accounts.ido.operator = operator;
+    ctx.accounts.ido.treasurer = treasurer;
+
+    emit!(SetRolesEvent {
+        operator,
+        treasurer,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct SetRolesEvent {
+    operator: Pubkey,
+    treasurer: Pubkey,
+}
@@ -1,62 +1,44 @@
-use crate::{account::*, config::*, error::*, helpers::*};
+use crate::{account::*, config::*, helpers::*};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct StartSaleRound<'info> {
-    #[account(mut, seeds = [b"ido"], bump = ido.bump)]
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
     ido: Account<'info, Ido>,
-    #[account(address = ido.authority)]
-    ido_authority: Signer<'info>,
-    acdm_mint_authority: Signer<'info>,
-    #[account(mut, address = ido.acdm_mint, mint::authority = acdm_mint_authority)]
-    acdm_mint: Account<'info, Mint>,
-    #[account(mut, associated_token::authority = ido, associated_token::mint = acdm_mint)]
-    ido_acdm: Account<'info, TokenAccount>,
-    token_program: Program<'info, Token>,
-}
-impl<'info> StartSaleRound<'info> {
-    fn mint_acdm(&self, amount: u64) -> Result<()> {
-        let cpi_accounts = MintTo {
-            mint: self.acdm_mint.to_account_info(),
-            to: self.ido_acdm.to_account_info(),
-            authority: self.acdm_mint_authority.to_account_info(),
-        };
-        let cpi_program = self.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::mint_to(cpi_ctx, amount)
-    }
-
-    fn can_start_sale_round(&self, ts: u32) -> Result<()> {
-        match self.ido.state {
-            IdoState::NotStarted => Ok(()),
-            IdoState::SaleRound => err!(IdoError::RoundAlreadyStarted),
-            IdoState::TradeRound => round_time_over(&self.ido, ts),
-            IdoState::Over => err!(IdoError::IdoIsOver),
-        }
-    }
+    #[account(address = ido.operator)]
+    operator: Signer<'info>,
+    #[account(mut, address = ido.acdm_mint)]
+    acdm_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    ido_acdm: InterfaceAccount<'info, TokenAccount>,
+    acdm_token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn start_sale_round(ctx: Context<StartSaleRound>) -> Result<()> {
-    let ts = Clock::get()?.unix_timestamp as u32;
-
-    ctx.accounts.can_start_sale_round(ts)?;
+    let ts = Clock::get()?.unix_timestamp;
 
-    ctx.accounts.ido.state = IdoState::SaleRound;
-    ctx.accounts.ido.current_state_start_ts = ts;
-    ctx.accounts.ido.acdm_price = if ctx.accounts.ido.sale_rounds_started == 0 {
-        INITIAL_PRICE
-    } else {
-        sale_price_formula(ctx.accounts.ido.acdm_price)
-    };
-    ctx.accounts.ido.sale_rounds_started += 1;
+    not_paused(&ctx.accounts.ido)?;
+    can_start_sale_round(&ctx.accounts.ido, ts)?;
 
-    let amount_to_mint = ctx.accounts.ido.usdc_traded / ctx.accounts.ido.acdm_price;
-    ctx.accounts.mint_acdm(amount_to_mint)?;
+    let amount_to_mint = ctx.accounts.ido.start_sale_round(ts)?;
+    mint_ido_acdm(
+        &ctx.accounts.ido,
+        &ctx.accounts.acdm_mint,
+        &ctx.accounts.ido_acdm,
+        &ctx.accounts.acdm_token_program,
+        amount_to_mint,
+    )?;
 
     emit!(StartSaleRoundEvent {
         acdm_price: ctx.accounts.ido.acdm_price,
         minted_amount: amount_to_mint,
+        price_curve: ctx.accounts.ido.config.price_curve.clone(),
     });
 
     Ok(())
@@ -66,4 +48,5 @@ pub fn start_sale_round(ctx: Context<StartSaleRound>) -> Result<()> {
 struct StartSaleRoundEvent {
     acdm_price: u64,
     minted_amount: u64,
+    price_curve: PriceCurve,
 }
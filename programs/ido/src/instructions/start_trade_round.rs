@@ -1,62 +1,39 @@
-use crate::{account::*, error::*, helpers::*};
+use crate::{account::*, helpers::*};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct StartTradeRound<'info> {
-    #[account(mut, seeds = [b"ido"], bump = ido.bump)]
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
     ido: Account<'info, Ido>,
-    #[account(mut, address = ido.authority)]
-    ido_authority: Signer<'info>,
+    #[account(address = ido.operator)]
+    operator: Signer<'info>,
     #[account(mut, address = ido.acdm_mint)]
-    acdm_mint: Account<'info, Mint>,
-    #[account(mut, associated_token::authority = ido, associated_token::mint = acdm_mint)]
-    ido_acdm: Account<'info, TokenAccount>,
-    token_program: Program<'info, Token>,
-}
-impl<'info> StartTradeRound<'info> {
-    fn burn_acdm(&self) -> Result<()> {
-        if self.ido_acdm.amount == 0 {
-            return Ok(());
-        }
-
-        let signer: &[&[&[u8]]] = &[&[b"ido".as_ref(), &[self.ido.bump]]];
-        let cpi_accounts = Burn {
-            mint: self.acdm_mint.to_account_info(),
-            from: self.ido_acdm.to_account_info(),
-            authority: self.ido.to_account_info(),
-        };
-        let cpi_program = self.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-        token::burn(cpi_ctx, self.ido_acdm.amount)
-    }
-
-    fn can_start_trade_round(&self, ts: u32) -> Result<()> {
-        match self.ido.state {
-            IdoState::NotStarted => err!(IdoError::NotSaleRound),
-            IdoState::SaleRound => {
-                if self.ido_acdm.amount == 0 {
-                    return Ok(());
-                }
-
-                round_time_over(&self.ido, ts)
-            }
-            IdoState::TradeRound => err!(IdoError::RoundAlreadyStarted),
-            IdoState::Over => err!(IdoError::IdoIsOver),
-        }
-    }
+    acdm_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    ido_acdm: InterfaceAccount<'info, TokenAccount>,
+    acdm_token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn start_trade_round(ctx: Context<StartTradeRound>) -> Result<()> {
-    let ts = Clock::get()?.unix_timestamp as u32;
+    let ts = Clock::get()?.unix_timestamp;
 
-    ctx.accounts.can_start_trade_round(ts)?;
+    not_paused(&ctx.accounts.ido)?;
+    can_start_trade_round(&ctx.accounts.ido, ctx.accounts.ido_acdm.amount, ts)?;
 
-    ctx.accounts.ido.state = IdoState::TradeRound;
-    ctx.accounts.ido.current_state_start_ts = ts;
-    ctx.accounts.ido.usdc_traded = 0;
+    ctx.accounts.ido.start_trade_round(ts);
 
-    ctx.accounts.burn_acdm()?;
+    burn_ido_acdm(
+        &ctx.accounts.ido,
+        &ctx.accounts.acdm_mint,
+        &ctx.accounts.ido_acdm,
+        &ctx.accounts.acdm_token_program,
+    )?;
 
     emit!(StartTradeRoundEvent {});
 
@@ -0,0 +1,37 @@
+use crate::{account::*, error::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Unpause<'info> {
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(address = ido.admin)]
+    admin: Signer<'info>,
+}
+
+pub fn unpause(ctx: Context<Unpause>) -> Result<()> {
+    let ts = Clock::get()?.unix_timestamp;
+
+    if !ctx.accounts.ido.paused {
+        return err!(IdoError::NotPaused);
+    }
+
+    // the round timer does not run while the IDO is paused
+    let paused_duration = ts.saturating_sub(ctx.accounts.ido.paused_at).max(0);
+    ctx.accounts.ido.current_state_start_ts = ctx
+        .accounts
+        .ido
+        .current_state_start_ts
+        .checked_add(paused_duration)
+        .ok_or(IdoError::Overflow)?;
+    ctx.accounts.ido.paused = false;
+
+    emit!(UnpauseEvent { paused_duration });
+
+    Ok(())
+}
+
+#[event]
+struct UnpauseEvent {
+    paused_duration: i64,
+}
@@ -0,0 +1,40 @@
+use crate::{account::*, config::*, error::*, math::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(address = ido.admin)]
+    admin: Signer<'info>,
+}
+
+/// the new config applies from the next sale round on,
+/// the issue and price of the first one can only change before it starts
+pub fn update_config(ctx: Context<UpdateConfig>, config: IdoConfig) -> Result<()> {
+    config.validate()?;
+
+    match ctx.accounts.ido.state {
+        IdoState::NotStarted => {
+            ctx.accounts.ido.usdc_traded = acdm_to_usdc(
+                config.initial_issue,
+                config.initial_price,
+                ctx.accounts.ido.acdm_decimals,
+                Rounding::Down,
+            )?;
+        }
+        IdoState::SaleRound | IdoState::TradeRound => {}
+        IdoState::Over => return err!(IdoError::IdoIsOver),
+    }
+
+    ctx.accounts.ido.config = config.clone();
+
+    emit!(UpdateConfigEvent { config });
+
+    Ok(())
+}
+
+#[event]
+struct UpdateConfigEvent {
+    config: IdoConfig,
+}
@@ -0,0 +1,115 @@
+use crate::{account::*, error::*, helpers::*, math::*, transfer::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct UpdateOrder<'info> {
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
+    ido: Account<'info, Ido>,
+    #[account(
+        mut,
+        seeds = [b"order", ido.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump = order.bump,
+    )]
+    order: Account<'info, Order>,
+    #[account(address = ido.acdm_mint)]
+    acdm_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::authority = order,
+        associated_token::mint = ido.acdm_mint,
+        associated_token::token_program = acdm_token_program,
+    )]
+    order_acdm: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = order.authority)]
+    seller: Signer<'info>,
+    #[account(mut)]
+    seller_acdm: InterfaceAccount<'info, TokenAccount>,
+    acdm_token_program: Interface<'info, TokenInterface>,
+}
+impl<'info> UpdateOrder<'info> {
+    fn deposit_acdm(&self, amount: u64) -> Result<u64> {
+        let cpi_accounts = TransferChecked {
+            from: self.seller_acdm.to_account_info(),
+            mint: self.acdm_mint.to_account_info(),
+            to: self.order_acdm.to_account_info(),
+            authority: self.seller.to_account_info(),
+        };
+        let cpi_program = self.acdm_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        transfer_checked_received(cpi_ctx, amount, self.acdm_mint.decimals)
+    }
+
+    fn withdraw_acdm(&self, id: u64, amount: u64) -> Result<()> {
+        let ido = self.ido.key();
+        let signer: &[&[&[u8]]] = &[&[
+            b"order".as_ref(),
+            ido.as_ref(),
+            &id.to_le_bytes(),
+            &[self.order.bump],
+        ]];
+        let cpi_accounts = TransferChecked {
+            from: self.order_acdm.to_account_info(),
+            mint: self.acdm_mint.to_account_info(),
+            to: self.seller_acdm.to_account_info(),
+            authority: self.order.to_account_info(),
+        };
+        let cpi_program = self.acdm_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        transfer_checked(cpi_ctx, amount, self.acdm_mint.decimals)
+    }
+}
+
+/// `original_amount` follows deposits and withdrawals,
+/// so it keeps being the amount the order was offering in total
+pub fn update_order(
+    ctx: Context<UpdateOrder>,
+    id: u64,
+    acdm_price: Option<u64>,
+    deposit_amount: u64,
+    withdraw_amount: u64,
+) -> Result<()> {
+    let ts = Clock::get()?.unix_timestamp;
+
+    is_trade_round(&ctx.accounts.ido)?;
+    if ctx.accounts.order.is_expired(&ctx.accounts.ido, ts) {
+        return err!(IdoError::OrderExpired);
+    }
+
+    // a repriced order goes to the back of the queue for `match_orders`
+    if let Some(acdm_price) = acdm_price {
+        ctx.accounts.order.price = acdm_price;
+        ctx.accounts.order.sequence = ctx.accounts.ido.next_sequence()?;
+    }
+
+    if deposit_amount != 0 {
+        not_paused(&ctx.accounts.ido)?;
+        let acdm_amount_received = ctx.accounts.deposit_acdm(deposit_amount)?;
+        ctx.accounts.order.original_amount =
+            add(ctx.accounts.order.original_amount, acdm_amount_received)?;
+    }
+
+    if withdraw_amount != 0 {
+        ctx.accounts.withdraw_acdm(id, withdraw_amount)?;
+        ctx.accounts.order.original_amount =
+            sub(ctx.accounts.order.original_amount, withdraw_amount)?;
+    }
+
+    ctx.accounts.order_acdm.reload()?;
+
+    emit!(UpdateOrderEvent {
+        id,
+        price: ctx.accounts.order.price,
+        amount: ctx.accounts.order_acdm.amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+struct UpdateOrderEvent {
+    id: u64,
+    price: u64,
+    amount: u64,
+}
@@ -1,30 +1,42 @@
-use crate::account::*;
+use crate::{account::*, transfer::*};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct WithdrawIdoUsdc<'info> {
-    #[account(mut, seeds = [b"ido"], bump = ido.bump)]
+    #[account(mut, seeds = [b"ido", ido.acdm_mint.as_ref()], bump = ido.bump)]
     ido: Account<'info, Ido>,
-    #[account(mut, address = ido.authority)]
-    ido_authority: Signer<'info>,
-    #[account(mut, associated_token::authority = ido, associated_token::mint = ido.usdc_mint)]
-    ido_usdc: Account<'info, TokenAccount>,
+    #[account(address = ido.treasurer)]
+    treasurer: Signer<'info>,
+    #[account(address = ido.usdc_mint)]
+    usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::authority = ido,
+        associated_token::mint = ido.usdc_mint,
+        associated_token::token_program = usdc_token_program,
+    )]
+    ido_usdc: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    to: Account<'info, TokenAccount>,
-    token_program: Program<'info, Token>,
+    to: InterfaceAccount<'info, TokenAccount>,
+    usdc_token_program: Interface<'info, TokenInterface>,
 }
 impl<'info> WithdrawIdoUsdc<'info> {
     fn transfer(&self) -> Result<()> {
-        let signer: &[&[&[u8]]] = &[&[b"ido".as_ref(), &[self.ido.bump]]];
-        let cpi_accounts = Transfer {
+        let signer: &[&[&[u8]]] = &[&[
+            b"ido".as_ref(),
+            self.ido.acdm_mint.as_ref(),
+            &[self.ido.bump],
+        ]];
+        let cpi_accounts = TransferChecked {
             from: self.ido_usdc.to_account_info(),
+            mint: self.usdc_mint.to_account_info(),
             to: self.to.to_account_info(),
             authority: self.ido.to_account_info(),
         };
-        let cpi_program = self.token_program.to_account_info();
+        let cpi_program = self.usdc_token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-        token::transfer(cpi_ctx, self.ido_usdc.amount)
+        transfer_checked(cpi_ctx, self.ido_usdc.amount, self.usdc_mint.decimals)
     }
 }
 
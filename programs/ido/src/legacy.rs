@@ -0,0 +1,76 @@
+//! layouts of accounts created by the first release of the program, which is version 0,
+//! only deserialized by the `migrate_*` instructions. Those accounts had no version,
+//! were not scoped by IDO and lived at `[b"ido"]`, `[b"order", id]` and `[b"member", authority]`,
+//! so they are moved to their current addresses and closed
+use crate::{account::*, error::*};
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(AnchorDeserialize)]
+pub struct IdoV0 {
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub state: IdoState,
+    pub acdm_mint: Pubkey,
+    pub usdc_mint: Pubkey,
+    pub acdm_price: u64,
+    pub usdc_traded: u64,
+    pub orders: u64,
+    pub round_time: u32,
+    pub current_state_start_ts: u32,
+    pub sale_rounds_started: u8,
+}
+impl IdoV0 {
+    pub const LEN: usize = 1 + 32 + 1 + 32 + 32 + 8 + 8 + 8 + 4 + 4 + 1;
+}
+
+#[derive(AnchorDeserialize)]
+pub struct OrderV0 {
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub price: u64,
+}
+impl OrderV0 {
+    pub const LEN: usize = 1 + 32 + 8;
+}
+
+#[derive(AnchorDeserialize)]
+pub struct MemberV0 {
+    /// the bump of the legacy address, which is checked by the seeds constraint
+    pub _bump: u8,
+    pub referer: Option<Pubkey>,
+}
+impl MemberV0 {
+    pub const LEN: usize = 1 + 33;
+}
+
+/// the layout of an account is told by its size, as version 0 has no version field
+pub fn legacy_data_len(account: &AccountInfo, discriminator: [u8; 8]) -> Result<usize> {
+    let data = account.try_borrow_data()?;
+    if data.len() < 8 || data[..8] != discriminator {
+        return err!(ErrorCode::AccountDiscriminatorMismatch);
+    }
+
+    Ok(data.len() - 8)
+}
+
+pub fn load_legacy<T: AnchorDeserialize>(account: &AccountInfo) -> Result<T> {
+    Ok(T::deserialize(&mut &account.try_borrow_data()?[8..])?)
+}
+
+/// sends all lamports of a migrated account to `destination` and hands it back to the system program
+pub fn close_legacy<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    let lamports = account.lamports();
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(IdoError::Overflow)?;
+    **account.try_borrow_mut_lamports()? = 0;
+
+    account.assign(&system_program::ID);
+    account.realloc(0, false)?;
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::instructions::*;
+use crate::{account::*, config::*, instructions::*};
 use anchor_lang::prelude::*;
 
 mod account;
@@ -6,7 +6,10 @@ mod config;
 mod error;
 mod helpers;
 mod instructions;
+mod legacy;
+mod math;
 mod referral;
+mod transfer;
 
 declare_id!("AUuf3MCis1CgAsFXHgson2r3g4VjqUdD7r3CUc8mEKj3");
 
@@ -14,20 +17,47 @@ declare_id!("AUuf3MCis1CgAsFXHgson2r3g4VjqUdD7r3CUc8mEKj3");
 pub mod ido {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, round_time: u32) -> Result<()> {
-        instructions::initialize(ctx, round_time)
+    pub fn initialize(ctx: Context<Initialize>, round_time: i64, config: IdoConfig) -> Result<()> {
+        instructions::initialize(ctx, round_time, config)
     }
 
-    pub fn register_member(ctx: Context<RegisterMember>, referer: Option<Pubkey>) -> Result<()> {
+    pub fn initialize_with_mint(
+        ctx: Context<InitializeWithMint>,
+        round_time: i64,
+        config: IdoConfig,
+        acdm_decimals: u8,
+    ) -> Result<()> {
+        instructions::initialize_with_mint(ctx, round_time, config, acdm_decimals)
+    }
+
+    pub fn register_member<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RegisterMember<'info>>,
+        referer: Option<Pubkey>,
+    ) -> Result<()> {
         instructions::register_member(ctx, referer)
     }
 
+    pub fn create_referral_code(ctx: Context<CreateReferralCode>, code: String) -> Result<()> {
+        instructions::create_referral_code(ctx, code)
+    }
+
+    pub fn register_member_with_code<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RegisterMemberWithCode<'info>>,
+        code: String,
+    ) -> Result<()> {
+        instructions::register_member_with_code(ctx, code)
+    }
+
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        instructions::claim_referral_rewards(ctx)
+    }
+
     pub fn start_sale_round(ctx: Context<StartSaleRound>) -> Result<()> {
         instructions::start_sale_round(ctx)
     }
 
     pub fn buy_acdm<'info>(
-        ctx: Context<'_, '_, '_, 'info, BuyAcdm<'info>>,
+        ctx: Context<'_, '_, 'info, 'info, BuyAcdm<'info>>,
         acdm_amount: u64,
     ) -> Result<()> {
         instructions::buy_acdm(ctx, acdm_amount)
@@ -37,22 +67,78 @@ pub mod ido {
         instructions::start_trade_round(ctx)
     }
 
-    pub fn add_order(ctx: Context<AddOrder>, acdm_amount: u64, acdm_price: u64) -> Result<()> {
-        instructions::add_order(ctx, acdm_amount, acdm_price)
+    pub fn crank(ctx: Context<Crank>) -> Result<()> {
+        instructions::crank(ctx)
+    }
+
+    pub fn add_order(
+        ctx: Context<AddOrder>,
+        acdm_amount: u64,
+        acdm_price: u64,
+        expiry: OrderExpiry,
+    ) -> Result<()> {
+        instructions::add_order(ctx, acdm_amount, acdm_price, expiry)
     }
 
     pub fn redeem_order<'info>(
-        ctx: Context<'_, '_, '_, 'info, RedeemOrder<'info>>,
+        ctx: Context<'_, '_, 'info, 'info, RedeemOrder<'info>>,
         id: u64,
         acdm_amount: u64,
     ) -> Result<()> {
         instructions::redeem_order(ctx, id, acdm_amount)
     }
 
+    pub fn redeem_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RedeemOrders<'info>>,
+        ids: Vec<u64>,
+        acdm_amount: u64,
+        max_average_price: u64,
+    ) -> Result<()> {
+        instructions::redeem_orders(ctx, ids, acdm_amount, max_average_price)
+    }
+
     pub fn remove_order(ctx: Context<RemoveOrder>, id: u64) -> Result<()> {
         instructions::remove_order(ctx, id)
     }
 
+    pub fn close_expired_order(ctx: Context<CloseExpiredOrder>, id: u64) -> Result<()> {
+        instructions::close_expired_order(ctx, id)
+    }
+
+    pub fn add_bid(ctx: Context<AddBid>, acdm_amount: u64, acdm_price: u64) -> Result<()> {
+        instructions::add_bid(ctx, acdm_amount, acdm_price)
+    }
+
+    pub fn fill_bid<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FillBid<'info>>,
+        id: u64,
+        acdm_amount: u64,
+    ) -> Result<()> {
+        instructions::fill_bid(ctx, id, acdm_amount)
+    }
+
+    pub fn match_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MatchOrders<'info>>,
+        order_id: u64,
+        bid_id: u64,
+    ) -> Result<()> {
+        instructions::match_orders(ctx, order_id, bid_id)
+    }
+
+    pub fn remove_bid(ctx: Context<RemoveBid>, id: u64) -> Result<()> {
+        instructions::remove_bid(ctx, id)
+    }
+
+    pub fn update_order(
+        ctx: Context<UpdateOrder>,
+        id: u64,
+        acdm_price: Option<u64>,
+        deposit_amount: u64,
+        withdraw_amount: u64,
+    ) -> Result<()> {
+        instructions::update_order(ctx, id, acdm_price, deposit_amount, withdraw_amount)
+    }
+
     pub fn withdraw_ido_usdc(ctx: Context<WithdrawIdoUsdc>) -> Result<()> {
         instructions::withdraw_ido_usdc(ctx)
     }
@@ -60,4 +146,40 @@ pub mod ido {
     pub fn end_ido(ctx: Context<EndIdo>) -> Result<()> {
         instructions::end_ido(ctx)
     }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, config: IdoConfig) -> Result<()> {
+        instructions::update_config(ctx, config)
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::propose_authority(ctx, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::accept_authority(ctx)
+    }
+
+    pub fn pause(ctx: Context<Pause>) -> Result<()> {
+        instructions::pause(ctx)
+    }
+
+    pub fn unpause(ctx: Context<Unpause>) -> Result<()> {
+        instructions::unpause(ctx)
+    }
+
+    pub fn migrate_ido(ctx: Context<MigrateIdo>, config: IdoConfig) -> Result<()> {
+        instructions::migrate_ido(ctx, config)
+    }
+
+    pub fn migrate_order(ctx: Context<MigrateOrder>, id: u64) -> Result<()> {
+        instructions::migrate_order(ctx, id)
+    }
+
+    pub fn migrate_member(ctx: Context<MigrateMember>) -> Result<()> {
+        instructions::migrate_member(ctx)
+    }
+
+    pub fn set_roles(ctx: Context<SetRoles>, operator: Pubkey, treasurer: Pubkey) -> Result<()> {
+        instructions::set_roles(ctx, operator, treasurer)
+    }
 }
@@ -0,0 +1,195 @@
+use crate::{config::*, error::*};
+use anchor_lang::prelude::*;
+
+#[derive(Clone, Copy)]
+pub(crate) enum Rounding {
+    Down,
+    Up,
+}
+
+/// `a * b / c` with a u128 intermediate, so only the result has to fit into u64
+pub(crate) fn mul_div(a: u64, b: u64, c: u64, rounding: Rounding) -> Result<u64> {
+    if c == 0 {
+        return err!(IdoError::DivisionByZero);
+    }
+
+    let numerator = a as u128 * b as u128;
+    let denominator = c as u128;
+    let mut quotient = numerator / denominator;
+    if let Rounding::Up = rounding {
+        if quotient * denominator != numerator {
+            quotient += 1;
+        }
+    }
+
+    Ok(u64::try_from(quotient).map_err(|_| IdoError::Overflow)?)
+}
+
+pub(crate) fn add(a: u64, b: u64) -> Result<u64> {
+    Ok(a.checked_add(b).ok_or(IdoError::Overflow)?)
+}
+
+pub(crate) fn sub(a: u64, b: u64) -> Result<u64> {
+    Ok(a.checked_sub(b).ok_or(IdoError::Overflow)?)
+}
+
+fn acdm_unit(acdm_decimals: u8) -> Result<u64> {
+    Ok(10u64
+        .checked_pow(acdm_decimals as u32)
+        .ok_or(IdoError::Overflow)?)
+}
+
+/// `price` is in USDC base units per whole ACDM
+pub(crate) fn acdm_to_usdc(
+    acdm_amount: u64,
+    price: u64,
+    acdm_decimals: u8,
+    rounding: Rounding,
+) -> Result<u64> {
+    mul_div(acdm_amount, price, acdm_unit(acdm_decimals)?, rounding)
+}
+
+/// `price` is in USDC base units per whole ACDM
+pub(crate) fn usdc_to_acdm(
+    usdc_amount: u64,
+    price: u64,
+    acdm_decimals: u8,
+    rounding: Rounding,
+) -> Result<u64> {
+    mul_div(usdc_amount, acdm_unit(acdm_decimals)?, price, rounding)
+}
+
+/// the first release priced ACDM in USDC base units per ACDM base unit
+pub(crate) fn legacy_price_to_current(legacy_price: u64, acdm_decimals: u8) -> Result<u64> {
+    mul_div(legacy_price, acdm_unit(acdm_decimals)?, 1, Rounding::Down)
+}
+
+/// rounded down, so fees taken with bps summing up to at most `MAX_BPS`
+/// never add up to more than `amount`
+pub(crate) fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    mul_div(amount, bps as u64, MAX_BPS as u64, Rounding::Down)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// xorshift64, good enough to spread test inputs without extra dependencies
+    struct Rng(u64);
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, max: u64) -> u64 {
+            self.next() % max
+        }
+
+        fn bps(&mut self, max: u16) -> u16 {
+            self.below(max as u64 + 1) as u16
+        }
+    }
+
+    const RUNS: usize = 10_000;
+
+    #[test]
+    fn mul_div_rounds_in_the_requested_direction() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..RUNS {
+            let (a, b, c) = (rng.next(), rng.next() >> 32, rng.next() | 1);
+            let exact = a as u128 * b as u128;
+
+            let down = mul_div(a, b, c, Rounding::Down).unwrap() as u128;
+            let up = mul_div(a, b, c, Rounding::Up).unwrap() as u128;
+
+            assert!(down * c as u128 <= exact);
+            assert!(up * c as u128 >= exact);
+            assert!(up - down <= 1);
+        }
+
+        assert!(mul_div(u64::MAX, u64::MAX, 1, Rounding::Down).is_err());
+        assert!(mul_div(1, 1, 0, Rounding::Down).is_err());
+    }
+
+    /// `part` is at most the exact share of `total` and less than one base unit below it
+    fn assert_rounded_down_share(part: u64, total: u64, bps: u16) {
+        let exact = total as u128 * bps as u128;
+        let part = part as u128 * MAX_BPS as u128;
+
+        assert!(part <= exact);
+        assert!(exact - part < MAX_BPS as u128);
+    }
+
+    #[test]
+    fn fee_parts_are_rounded_down_shares() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..RUNS {
+            let total = rng.next() >> rng.below(64);
+            let fee_bps = rng.bps(MAX_BPS);
+            let mut referer_bps = Vec::new();
+            let mut referer_bps_left = fee_bps;
+            for _ in 0..rng.below(MAX_REFERRAL_DEPTH as u64 + 1) {
+                let bps = rng.bps(referer_bps_left);
+                referer_bps_left -= bps;
+                referer_bps.push(bps);
+            }
+
+            let fee = bps_of(total, fee_bps).unwrap();
+            assert_rounded_down_share(fee, total, fee_bps);
+
+            let mut to_referers = 0;
+            for &bps in &referer_bps {
+                let part = bps_of(total, bps).unwrap();
+                assert_rounded_down_share(part, total, bps);
+                to_referers = add(to_referers, part).unwrap();
+            }
+
+            // the IDO keeps what is left of the fee, which is less than one base unit below
+            // its exact share and gains less than one base unit per referer from rounding
+            let to_ido = sub(fee, to_referers).unwrap() as u128 * MAX_BPS as u128;
+            let exact_to_ido = total as u128 * referer_bps_left as u128;
+            assert!(to_ido + MAX_BPS as u128 > exact_to_ido);
+            assert!(to_ido <= exact_to_ido + MAX_BPS as u128 * referer_bps.len() as u128);
+        }
+    }
+
+    #[test]
+    fn buyer_pays_at_least_the_value_received() {
+        let mut rng = Rng(0xd1b5_4a32_d192_ed03);
+        for _ in 0..RUNS {
+            let acdm_amount = rng.next() >> 34;
+            let price = (rng.next() >> 34) | 1;
+            let acdm_decimals = rng.below(10) as u8;
+
+            let usdc_amount =
+                acdm_to_usdc(acdm_amount, price, acdm_decimals, Rounding::Up).unwrap();
+
+            assert!(
+                usdc_to_acdm(usdc_amount, price, acdm_decimals, Rounding::Down).unwrap()
+                    >= acdm_amount
+            );
+        }
+    }
+
+    #[test]
+    fn legacy_prices_keep_the_value_of_an_acdm_amount() {
+        let mut rng = Rng(0x8cb9_2ba7_2f3d_8dd7);
+        for _ in 0..RUNS {
+            let acdm_amount = rng.next() >> 34;
+            let legacy_price = rng.next() >> 44;
+            let acdm_decimals = rng.below(10) as u8;
+
+            let price = legacy_price_to_current(legacy_price, acdm_decimals).unwrap();
+
+            assert_eq!(
+                acdm_to_usdc(acdm_amount, price, acdm_decimals, Rounding::Down).unwrap(),
+                acdm_amount * legacy_price
+            );
+        }
+
+        assert!(legacy_price_to_current(u64::MAX, 1).is_err());
+    }
+}
@@ -1,19 +1,27 @@
-use crate::{account::*, error::*, ID};
+use crate::{account::*, error::*, math::*, transfer::*, ID};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 pub fn get_referer_member<'info>(
-    remaining_accounts: &[AccountInfo<'info>],
+    referer_member: Option<&'info AccountInfo<'info>>,
+    ido: Pubkey,
     referer: Pubkey,
 ) -> Result<Account<'info, Member>> {
-    if remaining_accounts.is_empty() {
-        return err!(IdoError::RefererMemberAccountNotProvided);
-    }
-    let referer_member = Account::<Member>::try_from(&remaining_accounts[0])?;
+    let referer_member = match referer_member {
+        Some(referer_member) => Account::<Member>::try_from(referer_member)?,
+        None => return err!(IdoError::RefererMemberAccountNotProvided),
+    };
 
-    let pda_key =
-        Pubkey::create_program_address(&[b"member", referer.as_ref(), &[referer_member.bump]], &ID)
-            .map_err(|_| IdoError::RefererPda)?;
+    let pda_key = Pubkey::create_program_address(
+        &[
+            b"member",
+            ido.as_ref(),
+            referer.as_ref(),
+            &[referer_member.bump],
+        ],
+        &ID,
+    )
+    .map_err(|_| IdoError::RefererPda)?;
     if referer_member.key() != pda_key {
         return err!(IdoError::RefererPda);
     }
@@ -21,75 +29,150 @@ pub fn get_referer_member<'info>(
     Ok(referer_member)
 }
 
+/// loads the member account of `authority`, which must be its PDA
+pub fn get_member<'info>(
+    member: &'info AccountInfo<'info>,
+    ido: Pubkey,
+    authority: Pubkey,
+) -> Result<Account<'info, Member>> {
+    let member = Account::<Member>::try_from(member)?;
+
+    let pda_key = Pubkey::create_program_address(
+        &[b"member", ido.as_ref(), authority.as_ref(), &[member.bump]],
+        &ID,
+    )
+    .map_err(|_| ErrorCode::ConstraintSeeds)?;
+    if member.key() != pda_key {
+        return err!(ErrorCode::ConstraintSeeds);
+    }
+
+    Ok(member)
+}
+
+/// counts the member being registered by `authority` as a referee of `referer`,
+/// whose member account is expected first in `remaining_accounts`;
+/// only self-referral has to be rejected, as a member being registered
+/// can't be anyone's referer yet, so no longer cycle can form
+pub fn add_referee<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    ido: &Account<Ido>,
+    authority: Pubkey,
+    referer: Pubkey,
+) -> Result<()> {
+    if referer == authority {
+        return err!(IdoError::SelfReferral);
+    }
+
+    let mut referer_member = get_referer_member(remaining_accounts.first(), ido.key(), referer)?;
+    referer_member.referees = referer_member
+        .referees
+        .checked_add(1)
+        .ok_or(IdoError::Overflow)?;
+    referer_member.exit(&ID)
+}
+
+fn transfer_usdc<'info>(
+    usdc_mint: &InterfaceAccount<'info, Mint>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    authority: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+    to: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<u64> {
+    if amount == 0 {
+        return Ok(0);
+    }
+    let cpi_accounts = TransferChecked {
+        from: from.to_account_info(),
+        mint: usdc_mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: authority.clone(),
+    };
+    let cpi_program = token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    transfer_checked_received(cpi_ctx, amount, usdc_mint.decimals)
+}
+
+/// credits referer fees to the rewards of the referers' member accounts,
+/// which are expected in `remaining_accounts` in order of referral level,
+/// and pays for them out of `from`, signed for with `signer` if `authority` is a PDA;
+/// fees are scaled down to what the referral vault received if the USDC mint withholds
+/// a transfer fee, so the vault always covers the rewards;
+/// returns how many of `remaining_accounts` were used
 #[allow(clippy::too_many_arguments)]
 pub fn send_to_referers_and_ido<'info>(
-    mut usdc_amount_to_ido: u64,
-    usdc_amount_to_referer: u64,
-    usdc_amount_to_referer2: u64,
+    usdc_amount_total: u64,
+    usdc_amount_to_ido: u64,
+    referer_fees_bps: &[u16],
+    ido: Pubkey,
     referring_member: &Account<'info, Member>,
-    buyer: &Signer<'info>,
-    buyer_usdc: &Account<'info, TokenAccount>,
-    ido_usdc: &Account<'info, TokenAccount>,
-    token_program: &Program<'info, Token>,
-    remaining_accounts: &[AccountInfo<'info>],
-) -> Result<()> {
-    if let Some(referer) = referring_member.referer {
-        let referer_member = get_referer_member(remaining_accounts, referer)?;
+    usdc_mint: &InterfaceAccount<'info, Mint>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    authority: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+    ido_usdc: &InterfaceAccount<'info, TokenAccount>,
+    referral_usdc: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<usize> {
+    let mut usdc_amount_to_referers = 0;
+    let mut referers = Vec::with_capacity(referer_fees_bps.len());
+    let mut next_referer = referring_member.referer;
 
-        if remaining_accounts.len() < 2 {
-            return err!(IdoError::RefererTokenAccountNotProvided);
-        }
-        let referer_usdc = Account::<TokenAccount>::try_from(&remaining_accounts[1])?;
-        if referer_usdc.owner != referer {
-            return err!(IdoError::RefererOwner);
-        }
+    for (level, &referer_fee_bps) in referer_fees_bps.iter().enumerate() {
+        let referer = match next_referer {
+            Some(referer) => referer,
+            None => break,
+        };
 
-        usdc_amount_to_ido -= usdc_amount_to_referer;
+        let referer_member = get_referer_member(remaining_accounts.get(level), ido, referer)?;
 
-        msg!("sending fee to first referer");
+        let usdc_amount_to_referer = bps_of(usdc_amount_total, referer_fee_bps)?;
+        usdc_amount_to_referers = add(usdc_amount_to_referers, usdc_amount_to_referer)?;
 
-        let cpi_accounts = Transfer {
-            from: buyer_usdc.to_account_info(),
-            to: referer_usdc.to_account_info(),
-            authority: buyer.to_account_info(),
-        };
-        let cpi_program = token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, usdc_amount_to_referer)?;
-
-        if let Some(referer2) = referer_member.referer {
-            if remaining_accounts.len() < 3 {
-                return err!(IdoError::RefererTokenAccountNotProvided);
-            }
-            let referer2_usdc = Account::<TokenAccount>::try_from(&remaining_accounts[2])?;
-            if referer2_usdc.owner != referer2 {
-                return err!(IdoError::RefererOwner);
-            }
-
-            usdc_amount_to_ido -= usdc_amount_to_referer2;
-
-            msg!("sending fee to second referer");
-
-            let cpi_accounts = Transfer {
-                from: buyer_usdc.to_account_info(),
-                to: referer2_usdc.to_account_info(),
-                authority: buyer.to_account_info(),
-            };
-            let cpi_program = token_program.to_account_info();
-            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-            token::transfer(cpi_ctx, usdc_amount_to_referer2)?;
-        }
+        next_referer = referer_member.referer;
+        referers.push((referer_member, usdc_amount_to_referer));
     }
 
-    if usdc_amount_to_ido == 0 {
-        return Ok(());
+    let usdc_amount_received = transfer_usdc(
+        usdc_mint,
+        from,
+        authority,
+        signer,
+        referral_usdc,
+        token_program,
+        usdc_amount_to_referers,
+    )?;
+
+    let referers_used = referers.len();
+    for (level, (mut referer_member, mut usdc_amount_to_referer)) in
+        referers.into_iter().enumerate()
+    {
+        if usdc_amount_received != usdc_amount_to_referers {
+            usdc_amount_to_referer = mul_div(
+                usdc_amount_to_referer,
+                usdc_amount_received,
+                usdc_amount_to_referers,
+                Rounding::Down,
+            )?;
+        }
+
+        referer_member.rewards = add(referer_member.rewards, usdc_amount_to_referer)?;
+        referer_member.rewards_earned[level] =
+            add(referer_member.rewards_earned[level], usdc_amount_to_referer)?;
+        referer_member.exit(&ID)?;
     }
-    let cpi_accounts = Transfer {
-        from: buyer_usdc.to_account_info(),
-        to: ido_usdc.to_account_info(),
-        authority: buyer.to_account_info(),
-    };
-    let cpi_program = token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, usdc_amount_to_ido)
+
+    transfer_usdc(
+        usdc_mint,
+        from,
+        authority,
+        signer,
+        ido_usdc,
+        token_program,
+        sub(usdc_amount_to_ido, usdc_amount_to_referers)?,
+    )?;
+
+    Ok(referers_used)
 }
@@ -0,0 +1,63 @@
+//! Both mints may belong to either token program. Token-2022 mints may withhold
+//! a fee from every transfer, so amounts that are recorded are measured at the receiving
+//! account, and withheld fees are moved to the mint before a token account is closed.
+use crate::math::*;
+use anchor_lang::{prelude::*, solana_program::program::invoke};
+use anchor_spl::token_interface::{
+    self,
+    spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{instruction::harvest_withheld_tokens_to_mint, TransferFeeConfig},
+            BaseStateWithExtensions, StateWithExtensions,
+        },
+        state,
+    },
+    CloseAccount,
+};
+
+pub use anchor_spl::token_interface::{transfer_checked, TransferChecked};
+
+fn token_amount(account: &AccountInfo) -> Result<u64> {
+    let data = account.try_borrow_data()?;
+    Ok(StateWithExtensions::<state::Account>::unpack(&data)?
+        .base
+        .amount)
+}
+
+fn has_transfer_fee(mint: &AccountInfo) -> Result<bool> {
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<state::Mint>::unpack(&data)?;
+    Ok(mint.get_extension::<TransferFeeConfig>().is_ok())
+}
+
+/// same as `transfer_checked`, but returns the amount `to` received,
+/// which is less than `amount` if the mint withholds a transfer fee
+pub fn transfer_checked_received<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<u64> {
+    let to = ctx.accounts.to.clone();
+    let amount_before = token_amount(&to)?;
+    transfer_checked(ctx, amount, decimals)?;
+    sub(token_amount(&to)?, amount_before)
+}
+
+/// Token-2022 refuses to close accounts holding withheld fees,
+/// so they are harvested to `mint` first, which anyone may do
+pub fn close_account<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, CloseAccount<'info>>,
+    mint: &AccountInfo<'info>,
+) -> Result<()> {
+    if ctx.program.key() == spl_token_2022::ID && has_transfer_fee(mint)? {
+        let ix = harvest_withheld_tokens_to_mint(
+            &spl_token_2022::ID,
+            mint.key,
+            &[ctx.accounts.account.key],
+        )?;
+        invoke(&ix, &[mint.clone(), ctx.accounts.account.clone()])?;
+    }
+
+    token_interface::close_account(ctx)
+}
@@ -1,59 +1,214 @@
-import { BN } from "@project-serum/anchor";
+import { BN } from "@coral-xyz/anchor";
 import {
+  AccountMeta,
   Keypair,
   PublicKey,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
 } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddress,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
 import { Context } from "./ctx";
+import { findPDA } from "./utils";
+
+export interface IdoConfig {
+  initialIssue: BN;
+  initialPrice: BN;
+  priceCurve:
+    | { geometric: { numerator: BN; denominator: BN; increment: BN } }
+    | { linear: { step: BN } }
+    | { percentage: { growthBps: number } }
+    | { fixed: {} }
+    | { table: { prices: BN[] } };
+  fees: {
+    saleRefererBps: number[];
+    tradeFeeBps: number;
+    tradeRefererBps: number[];
+  };
+}
 
 export async function initialize(
   ctx: Context,
-  roundTime: number
+  roundTime: number,
+  config: IdoConfig
 ): Promise<void> {
   await ctx.program.methods
-    .initialize(roundTime)
+    .initialize(new BN(roundTime), config)
     .accounts({
       ido: ctx.ido,
-      idoAuthority: ctx.idoAuthority.publicKey,
+      admin: ctx.admin.publicKey,
+      acdmMintAuthority: ctx.acdmMintAuthority.publicKey,
       acdmMint: ctx.acdmMint,
       idoAcdm: ctx.idoAcdm,
       usdcMint: ctx.usdcMint,
       idoUsdc: ctx.idoUsdc,
+      referralUsdc: ctx.referralUsdc,
       rent: SYSVAR_RENT_PUBKEY,
+      acdmTokenProgram: ctx.acdmTokenProgram,
+      usdcTokenProgram: ctx.usdcTokenProgram,
       systemProgram: SystemProgram.programId,
     })
-    .signers([ctx.idoAuthority])
+    .signers([ctx.admin, ctx.acdmMintAuthority])
     .rpc();
 }
 
+export async function initializeWithMint(
+  ctx: Context,
+  acdmMint: Keypair,
+  roundTime: number,
+  config: IdoConfig,
+  acdmDecimals: number,
+  acdmTokenProgram: PublicKey = TOKEN_PROGRAM_ID
+): Promise<PublicKey> {
+  const ido = await findPDA(ctx, [
+    Buffer.from("ido"),
+    acdmMint.publicKey.toBuffer(),
+  ]);
+
+  await ctx.program.methods
+    .initializeWithMint(new BN(roundTime), config, acdmDecimals)
+    .accounts({
+      acdmMint: acdmMint.publicKey,
+      ido,
+      admin: ctx.admin.publicKey,
+      idoAcdm: await getAssociatedTokenAddress(
+        acdmMint.publicKey,
+        ido,
+        true,
+        acdmTokenProgram
+      ),
+      usdcMint: ctx.usdcMint,
+      idoUsdc: await ctx.usdcATA(ido),
+      referralUsdc: await findPDA(ctx, [
+        Buffer.from("referral_usdc"),
+        ido.toBuffer(),
+      ]),
+      rent: SYSVAR_RENT_PUBKEY,
+      acdmTokenProgram,
+      usdcTokenProgram: ctx.usdcTokenProgram,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([ctx.admin, acdmMint])
+    .rpc();
+
+  return ido;
+}
+
 export async function registerMember(
   ctx: Context,
   user: Keypair,
   referer: PublicKey
 ): Promise<void> {
   const member = await ctx.member(user.publicKey);
+  const remainingAccounts = await refererAccounts(ctx, referer, 1);
+
+  await ctx.program.methods
+    .registerMember(referer)
+    .accounts({
+      ido: ctx.ido,
+      member,
+      authority: user.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(remainingAccounts)
+    .signers([user])
+    .rpc();
+}
+
+export async function createReferralCode(
+  ctx: Context,
+  user: Keypair,
+  code: string
+): Promise<void> {
+  await ctx.program.methods
+    .createReferralCode(code)
+    .accounts({
+      ido: ctx.ido,
+      member: await ctx.member(user.publicKey),
+      referralCode: await ctx.referralCode(code),
+      authority: user.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([user])
+    .rpc();
+}
+
+export async function registerMemberWithCode(
+  ctx: Context,
+  user: Keypair,
+  code: string
+): Promise<void> {
+  const referralCode = await ctx.referralCode(code);
+  const referer = (await ctx.program.account.referralCode.fetch(referralCode))
+    .authority;
+  const remainingAccounts = await refererAccounts(ctx, referer, 1);
+
+  await ctx.program.methods
+    .registerMemberWithCode(code)
+    .accounts({
+      ido: ctx.ido,
+      referralCode,
+      member: await ctx.member(user.publicKey),
+      authority: user.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(remainingAccounts)
+    .signers([user])
+    .rpc();
+}
+
+async function referralDepth(
+  ctx: Context,
+  round: "sale" | "trade"
+): Promise<number> {
+  const fees = (await ctx.program.account.ido.fetch(ctx.ido)).config.fees;
+  return (round == "sale" ? fees.saleRefererBps : fees.tradeRefererBps)
+    .length;
+}
+
+async function refererAccounts(
+  ctx: Context,
+  referer: PublicKey | null,
+  depth: number
+): Promise<AccountMeta[]> {
   const remainingAccounts = [];
 
-  if (referer != null) {
+  for (let level = 0; level < depth && referer; level++) {
     const refererMember = await ctx.member(referer);
 
     remainingAccounts.push({
       pubkey: refererMember,
-      isWritable: false,
+      isWritable: true,
       isSigner: false,
     });
+
+    referer = (
+      await ctx.program.account.member.fetchNullable(refererMember)
+    )?.referer;
   }
 
+  return remainingAccounts;
+}
+
+export async function claimReferralRewards(
+  ctx: Context,
+  user: Keypair
+): Promise<void> {
   await ctx.program.methods
-    .registerMember(referer)
+    .claimReferralRewards()
     .accounts({
-      member,
+      ido: ctx.ido,
+      usdcMint: ctx.usdcMint,
+      member: await ctx.member(user.publicKey),
       authority: user.publicKey,
-      systemProgram: SystemProgram.programId,
+      referralUsdc: ctx.referralUsdc,
+      to: await ctx.usdcATA(user.publicKey),
+      usdcTokenProgram: ctx.usdcTokenProgram,
     })
-    .remainingAccounts(remainingAccounts)
     .signers([user])
     .rpc();
 }
@@ -63,13 +218,12 @@ export async function startSaleRound(ctx: Context): Promise<void> {
     .startSaleRound()
     .accounts({
       ido: ctx.ido,
-      idoAuthority: ctx.idoAuthority.publicKey,
-      acdmMintAuthority: ctx.acdmMintAuthority.publicKey,
+      operator: ctx.operator.publicKey,
       acdmMint: ctx.acdmMint,
       idoAcdm: ctx.idoAcdm,
-      tokenProgram: TOKEN_PROGRAM_ID,
+      acdmTokenProgram: ctx.acdmTokenProgram,
     })
-    .signers([ctx.idoAuthority, ctx.acdmMintAuthority])
+    .signers([ctx.operator])
     .rpc();
 }
 
@@ -82,50 +236,27 @@ export async function buyAcdm(
   const buyerAcdm = await ctx.acdmATA(buyer.publicKey);
   const buyerUsdc = await ctx.usdcATA(buyer.publicKey);
 
-  const remainingAccounts = [];
-
-  const referer = (await ctx.program.account.member.fetch(buyerMember)).referer;
-
-  if (referer) {
-    const refererMember = await ctx.member(referer);
-    const refererUsdc = await ctx.usdcATA(referer);
-
-    remainingAccounts.push({
-      pubkey: refererMember,
-      isWritable: false,
-      isSigner: false,
-    });
-    remainingAccounts.push({
-      pubkey: refererUsdc,
-      isWritable: true,
-      isSigner: false,
-    });
-
-    const referer2 = (await ctx.program.account.member.fetch(refererMember))
-      .referer;
-
-    if (referer2) {
-      const referer2Usdc = await ctx.usdcATA(referer2);
-
-      remainingAccounts.push({
-        pubkey: referer2Usdc,
-        isWritable: true,
-        isSigner: false,
-      });
-    }
-  }
+  const remainingAccounts = await refererAccounts(
+    ctx,
+    (await ctx.program.account.member.fetch(buyerMember)).referer,
+    await referralDepth(ctx, "sale")
+  );
 
   await ctx.program.methods
     .buyAcdm(amount)
     .accounts({
       ido: ctx.ido,
+      acdmMint: ctx.acdmMint,
+      usdcMint: ctx.usdcMint,
       idoAcdm: ctx.idoAcdm,
       idoUsdc: ctx.idoUsdc,
+      referralUsdc: ctx.referralUsdc,
       buyer: buyer.publicKey,
       buyerMember,
       buyerAcdm,
       buyerUsdc,
-      tokenProgram: TOKEN_PROGRAM_ID,
+      acdmTokenProgram: ctx.acdmTokenProgram,
+      usdcTokenProgram: ctx.usdcTokenProgram,
     })
     .remainingAccounts(remainingAccounts)
     .signers([buyer])
@@ -137,12 +268,24 @@ export async function startTradeRound(ctx: Context): Promise<void> {
     .startTradeRound()
     .accounts({
       ido: ctx.ido,
-      idoAuthority: ctx.idoAuthority.publicKey,
+      operator: ctx.operator.publicKey,
+      acdmMint: ctx.acdmMint,
+      idoAcdm: ctx.idoAcdm,
+      acdmTokenProgram: ctx.acdmTokenProgram,
+    })
+    .signers([ctx.operator])
+    .rpc();
+}
+
+export async function crank(ctx: Context): Promise<void> {
+  await ctx.program.methods
+    .crank()
+    .accounts({
+      ido: ctx.ido,
       acdmMint: ctx.acdmMint,
       idoAcdm: ctx.idoAcdm,
-      tokenProgram: TOKEN_PROGRAM_ID,
+      acdmTokenProgram: ctx.acdmTokenProgram,
     })
-    .signers([ctx.idoAuthority])
     .rpc();
 }
 
@@ -150,7 +293,8 @@ export async function addOrder(
   ctx: Context,
   amount: BN,
   price: BN,
-  seller: Keypair
+  seller: Keypair,
+  expiry: OrderExpiry = { never: {} }
 ): Promise<BN> {
   const orderId = (await ctx.program.account.ido.fetch(ctx.ido)).orders;
 
@@ -159,12 +303,12 @@ export async function addOrder(
   const sellerAcdm = await ctx.acdmATA(seller.publicKey);
 
   let listener: number;
-  const event = await new Promise((resolve, _reject) => {
+  const event = await new Promise((resolve, reject) => {
     listener = ctx.program.addEventListener("AddOrderEvent", (event, _) => {
       resolve(event);
     });
     ctx.program.methods
-      .addOrder(amount, price)
+      .addOrder(amount, price, expiry)
       .accounts({
         ido: ctx.ido,
         order,
@@ -173,11 +317,12 @@ export async function addOrder(
         seller: seller.publicKey,
         sellerAcdm,
         rent: SYSVAR_RENT_PUBKEY,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        acdmTokenProgram: ctx.acdmTokenProgram,
         systemProgram: SystemProgram.programId,
       })
       .signers([seller])
-      .rpc();
+      .rpc()
+      .catch(reject);
   });
   await ctx.program.removeEventListener(listener);
 
@@ -185,6 +330,11 @@ export async function addOrder(
   return event.id;
 }
 
+export type OrderExpiry =
+  | { never: {} }
+  | { at: { ts: BN } }
+  | { endOfRound: {} };
+
 export async function redeemOrder(
   ctx: Context,
   orderId: BN,
@@ -201,45 +351,29 @@ export async function redeemOrder(
   const sellerUsdc = await ctx.usdcATA(seller);
   const sellerMember = await ctx.member(seller);
 
-  const remainingAccounts = [];
-
-  const referer = (await ctx.program.account.member.fetch(sellerMember))
-    .referer;
-
-  if (referer) {
-    const refererMember = await ctx.member(referer);
-    const refererUsdc = await ctx.usdcATA(referer);
-
-    remainingAccounts.push({
-      pubkey: refererMember,
-      isWritable: false,
-      isSigner: false,
-    });
+  const remainingAccounts = await refererAccounts(
+    ctx,
+    (await ctx.program.account.member.fetch(sellerMember)).referer,
+    await referralDepth(ctx, "trade")
+  );
+  // buyers who are members get their stats kept
+  const buyerMember = await ctx.member(buyer.publicKey);
+  if (await ctx.program.account.member.fetchNullable(buyerMember)) {
     remainingAccounts.push({
-      pubkey: refererUsdc,
+      pubkey: buyerMember,
       isWritable: true,
       isSigner: false,
     });
-
-    const referer2 = (await ctx.program.account.member.fetch(refererMember))
-      .referer;
-
-    if (referer2) {
-      const referer2Usdc = await ctx.usdcATA(referer2);
-
-      remainingAccounts.push({
-        pubkey: referer2Usdc,
-        isWritable: true,
-        isSigner: false,
-      });
-    }
   }
 
   await ctx.program.methods
     .redeemOrder(orderId, amount)
     .accounts({
       ido: ctx.ido,
+      acdmMint: ctx.acdmMint,
+      usdcMint: ctx.usdcMint,
       idoUsdc: ctx.idoUsdc,
+      referralUsdc: ctx.referralUsdc,
       order,
       orderAcdm,
       buyer: buyer.publicKey,
@@ -248,7 +382,63 @@ export async function redeemOrder(
       seller,
       sellerMember,
       sellerUsdc,
-      tokenProgram: TOKEN_PROGRAM_ID,
+      acdmTokenProgram: ctx.acdmTokenProgram,
+      usdcTokenProgram: ctx.usdcTokenProgram,
+    })
+    .remainingAccounts(remainingAccounts)
+    .signers([buyer])
+    .rpc();
+}
+
+export async function redeemOrders(
+  ctx: Context,
+  orderIds: BN[],
+  amount: BN,
+  maxAveragePrice: BN,
+  buyer: Keypair
+): Promise<void> {
+  // the program reads exactly as many referers per order as it pays
+  const depth = (await ctx.program.account.ido.fetch(ctx.ido)).config.fees
+    .tradeRefererBps.length;
+
+  const remainingAccounts: AccountMeta[] = [];
+  for (const orderId of orderIds) {
+    const order = await ctx.order(orderId);
+    const seller = (await ctx.program.account.order.fetch(order)).authority;
+    const sellerMember = await ctx.member(seller);
+
+    remainingAccounts.push(
+      { pubkey: order, isWritable: true, isSigner: false },
+      {
+        pubkey: await ctx.orderAcdm(orderId),
+        isWritable: true,
+        isSigner: false,
+      },
+      { pubkey: seller, isWritable: false, isSigner: false },
+      { pubkey: sellerMember, isWritable: true, isSigner: false },
+      { pubkey: await ctx.usdcATA(seller), isWritable: true, isSigner: false },
+      ...(await refererAccounts(
+        ctx,
+        (await ctx.program.account.member.fetch(sellerMember)).referer,
+        depth
+      ))
+    );
+  }
+
+  await ctx.program.methods
+    .redeemOrders(orderIds, amount, maxAveragePrice)
+    .accounts({
+      ido: ctx.ido,
+      acdmMint: ctx.acdmMint,
+      usdcMint: ctx.usdcMint,
+      idoUsdc: ctx.idoUsdc,
+      referralUsdc: ctx.referralUsdc,
+      buyer: buyer.publicKey,
+      buyerAcdm: await ctx.acdmATA(buyer.publicKey),
+      buyerUsdc: await ctx.usdcATA(buyer.publicKey),
+      buyerMember: await ctx.member(buyer.publicKey),
+      acdmTokenProgram: ctx.acdmTokenProgram,
+      usdcTokenProgram: ctx.usdcTokenProgram,
     })
     .remainingAccounts(remainingAccounts)
     .signers([buyer])
@@ -267,27 +457,227 @@ export async function removeOrder(
   await ctx.program.methods
     .removeOrder(orderId)
     .accounts({
+      ido: ctx.ido,
+      acdmMint: ctx.acdmMint,
+      order,
+      orderAcdm,
+      seller: seller.publicKey,
+      sellerAcdm,
+      acdmTokenProgram: ctx.acdmTokenProgram,
+    })
+    .signers([seller])
+    .rpc();
+}
+
+export async function addBid(
+  ctx: Context,
+  amount: BN,
+  price: BN,
+  buyer: Keypair
+): Promise<BN> {
+  const bidId = (await ctx.program.account.ido.fetch(ctx.ido)).orders;
+
+  const bid = await ctx.bid(bidId);
+  const bidUsdc = await ctx.bidUsdc(bidId);
+  const buyerUsdc = await ctx.usdcATA(buyer.publicKey);
+
+  let listener: number;
+  const event = await new Promise((resolve, reject) => {
+    listener = ctx.program.addEventListener("AddBidEvent", (event, _) => {
+      resolve(event);
+    });
+    ctx.program.methods
+      .addBid(amount, price)
+      .accounts({
+        ido: ctx.ido,
+        bid,
+        usdcMint: ctx.usdcMint,
+        bidUsdc,
+        buyer: buyer.publicKey,
+        buyerMember: await ctx.member(buyer.publicKey),
+        buyerUsdc,
+        rent: SYSVAR_RENT_PUBKEY,
+        usdcTokenProgram: ctx.usdcTokenProgram,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc()
+      .catch(reject);
+  });
+  await ctx.program.removeEventListener(listener);
+
+  // @ts-ignore: event type
+  return event.id;
+}
+
+export async function fillBid(
+  ctx: Context,
+  bidId: BN,
+  amount: BN,
+  seller: Keypair
+): Promise<void> {
+  const bid = await ctx.bid(bidId);
+  const bidUsdc = await ctx.bidUsdc(bidId);
+
+  const buyer = (await ctx.program.account.bid.fetch(bid)).authority;
+  const sellerMember = await ctx.member(seller.publicKey);
+
+  const remainingAccounts = await refererAccounts(
+    ctx,
+    (await ctx.program.account.member.fetch(sellerMember)).referer,
+    await referralDepth(ctx, "trade")
+  );
+
+  await ctx.program.methods
+    .fillBid(bidId, amount)
+    .accounts({
+      ido: ctx.ido,
+      acdmMint: ctx.acdmMint,
+      usdcMint: ctx.usdcMint,
+      idoUsdc: ctx.idoUsdc,
+      referralUsdc: ctx.referralUsdc,
+      bid,
+      bidUsdc,
+      seller: seller.publicKey,
+      sellerAcdm: await ctx.acdmATA(seller.publicKey),
+      sellerUsdc: await ctx.usdcATA(seller.publicKey),
+      sellerMember,
+      buyer,
+      buyerAcdm: await ctx.acdmATA(buyer),
+      buyerMember: await ctx.member(buyer),
+      acdmTokenProgram: ctx.acdmTokenProgram,
+      usdcTokenProgram: ctx.usdcTokenProgram,
+    })
+    .remainingAccounts(remainingAccounts)
+    .signers([seller])
+    .rpc();
+}
+
+export async function matchOrders(
+  ctx: Context,
+  orderId: BN,
+  bidId: BN
+): Promise<void> {
+  const order = await ctx.order(orderId);
+  const bid = await ctx.bid(bidId);
+
+  const seller = (await ctx.program.account.order.fetch(order)).authority;
+  const buyer = (await ctx.program.account.bid.fetch(bid)).authority;
+  const sellerMember = await ctx.member(seller);
+
+  const remainingAccounts = await refererAccounts(
+    ctx,
+    (await ctx.program.account.member.fetch(sellerMember)).referer,
+    await referralDepth(ctx, "trade")
+  );
+
+  await ctx.program.methods
+    .matchOrders(orderId, bidId)
+    .accounts({
+      ido: ctx.ido,
+      acdmMint: ctx.acdmMint,
+      usdcMint: ctx.usdcMint,
+      idoUsdc: ctx.idoUsdc,
+      referralUsdc: ctx.referralUsdc,
       order,
+      orderAcdm: await ctx.orderAcdm(orderId),
+      seller,
+      sellerUsdc: await ctx.usdcATA(seller),
+      sellerMember,
+      bid,
+      bidUsdc: await ctx.bidUsdc(bidId),
+      buyer,
+      buyerAcdm: await ctx.acdmATA(buyer),
+      buyerMember: await ctx.member(buyer),
+      acdmTokenProgram: ctx.acdmTokenProgram,
+      usdcTokenProgram: ctx.usdcTokenProgram,
+    })
+    .remainingAccounts(remainingAccounts)
+    .rpc();
+}
+
+export async function removeBid(
+  ctx: Context,
+  bidId: BN,
+  buyer: Keypair
+): Promise<void> {
+  await ctx.program.methods
+    .removeBid(bidId)
+    .accounts({
+      ido: ctx.ido,
+      bid: await ctx.bid(bidId),
+      usdcMint: ctx.usdcMint,
+      bidUsdc: await ctx.bidUsdc(bidId),
+      buyer: buyer.publicKey,
+      buyerUsdc: await ctx.usdcATA(buyer.publicKey),
+      usdcTokenProgram: ctx.usdcTokenProgram,
+    })
+    .signers([buyer])
+    .rpc();
+}
+
+export async function updateOrder(
+  ctx: Context,
+  orderId: BN,
+  price: BN | null,
+  depositAmount: BN,
+  withdrawAmount: BN,
+  seller: Keypair
+): Promise<void> {
+  const order = await ctx.order(orderId);
+  const orderAcdm = await ctx.orderAcdm(orderId);
+  const sellerAcdm = await ctx.acdmATA(seller.publicKey);
+
+  await ctx.program.methods
+    .updateOrder(orderId, price, depositAmount, withdrawAmount)
+    .accounts({
+      ido: ctx.ido,
+      order,
+      acdmMint: ctx.acdmMint,
       orderAcdm,
       seller: seller.publicKey,
       sellerAcdm,
-      tokenProgram: TOKEN_PROGRAM_ID,
+      acdmTokenProgram: ctx.acdmTokenProgram,
     })
     .signers([seller])
     .rpc();
 }
 
+export async function closeExpiredOrder(
+  ctx: Context,
+  orderId: BN
+): Promise<void> {
+  const order = await ctx.order(orderId);
+  const orderAcdm = await ctx.orderAcdm(orderId);
+
+  const seller = (await ctx.program.account.order.fetch(order)).authority;
+
+  await ctx.program.methods
+    .closeExpiredOrder(orderId)
+    .accounts({
+      ido: ctx.ido,
+      acdmMint: ctx.acdmMint,
+      order,
+      orderAcdm,
+      seller,
+      sellerAcdm: await ctx.acdmATA(seller),
+      acdmTokenProgram: ctx.acdmTokenProgram,
+    })
+    .rpc();
+}
+
 export async function withdrawIdoUsdc(ctx: Context): Promise<void> {
   await ctx.program.methods
     .withdrawIdoUsdc()
     .accounts({
       ido: ctx.ido,
-      idoAuthority: ctx.idoAuthority.publicKey,
+      usdcMint: ctx.usdcMint,
+      treasurer: ctx.treasurer.publicKey,
       idoUsdc: ctx.idoUsdc,
-      to: await ctx.usdcATA(ctx.idoAuthority.publicKey),
-      tokenProgram: TOKEN_PROGRAM_ID,
+      to: await ctx.usdcATA(ctx.treasurer.publicKey),
+      usdcTokenProgram: ctx.usdcTokenProgram,
     })
-    .signers([ctx.idoAuthority])
+    .signers([ctx.treasurer])
     .rpc();
 }
 
@@ -296,8 +686,177 @@ export async function endIdo(ctx: Context): Promise<void> {
     .endIdo()
     .accounts({
       ido: ctx.ido,
-      idoAuthority: ctx.idoAuthority.publicKey,
+      operator: ctx.operator.publicKey,
+    })
+    .signers([ctx.operator])
+    .rpc();
+}
+
+export async function pause(ctx: Context): Promise<void> {
+  await ctx.program.methods
+    .pause()
+    .accounts({
+      ido: ctx.ido,
+      admin: ctx.admin.publicKey,
+    })
+    .signers([ctx.admin])
+    .rpc();
+}
+
+export async function unpause(ctx: Context): Promise<void> {
+  await ctx.program.methods
+    .unpause()
+    .accounts({
+      ido: ctx.ido,
+      admin: ctx.admin.publicKey,
+    })
+    .signers([ctx.admin])
+    .rpc();
+}
+
+export async function migrateIdo(
+  ctx: Context,
+  config: IdoConfig
+): Promise<void> {
+  const legacyIdo = await findPDA(ctx, [Buffer.from("ido")]);
+
+  await ctx.program.methods
+    .migrateIdo(config)
+    .accounts({
+      legacyIdo,
+      legacyIdoAcdm: await getAssociatedTokenAddress(
+        ctx.acdmMint,
+        legacyIdo,
+        true,
+        ctx.acdmTokenProgram
+      ),
+      legacyIdoUsdc: await getAssociatedTokenAddress(
+        ctx.usdcMint,
+        legacyIdo,
+        true,
+        ctx.usdcTokenProgram
+      ),
+      ido: ctx.ido,
+      admin: ctx.admin.publicKey,
+      acdmMintAuthority: ctx.acdmMintAuthority.publicKey,
+      acdmMint: ctx.acdmMint,
+      idoAcdm: ctx.idoAcdm,
+      usdcMint: ctx.usdcMint,
+      idoUsdc: ctx.idoUsdc,
+      referralUsdc: ctx.referralUsdc,
+      rent: SYSVAR_RENT_PUBKEY,
+      acdmTokenProgram: ctx.acdmTokenProgram,
+      usdcTokenProgram: ctx.usdcTokenProgram,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([ctx.admin, ctx.acdmMintAuthority])
+    .rpc();
+}
+
+export async function migrateOrder(ctx: Context, orderId: BN): Promise<void> {
+  const legacyOrder = await findPDA(ctx, [
+    Buffer.from("order"),
+    orderId.toArrayLike(Buffer, "le", 8),
+  ]);
+
+  await ctx.program.methods
+    .migrateOrder(orderId)
+    .accounts({
+      ido: ctx.ido,
+      legacyOrder,
+      legacyOrderAcdm: await getAssociatedTokenAddress(
+        ctx.acdmMint,
+        legacyOrder,
+        true,
+        ctx.acdmTokenProgram
+      ),
+      order: await ctx.order(orderId),
+      acdmMint: ctx.acdmMint,
+      orderAcdm: await ctx.orderAcdm(orderId),
+      payer: ctx.payer.publicKey,
+      acdmTokenProgram: ctx.acdmTokenProgram,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([ctx.payer])
+    .rpc();
+}
+
+export async function migrateMember(
+  ctx: Context,
+  authority: PublicKey
+): Promise<void> {
+  await ctx.program.methods
+    .migrateMember()
+    .accounts({
+      ido: ctx.ido,
+      authority,
+      legacyMember: await findPDA(ctx, [
+        Buffer.from("member"),
+        authority.toBuffer(),
+      ]),
+      member: await ctx.member(authority),
+      payer: ctx.payer.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([ctx.payer])
+    .rpc();
+}
+
+export async function setRoles(
+  ctx: Context,
+  operator: PublicKey,
+  treasurer: PublicKey
+): Promise<void> {
+  await ctx.program.methods
+    .setRoles(operator, treasurer)
+    .accounts({
+      ido: ctx.ido,
+      admin: ctx.admin.publicKey,
+    })
+    .signers([ctx.admin])
+    .rpc();
+}
+
+export async function updateConfig(
+  ctx: Context,
+  admin: Keypair,
+  config: IdoConfig
+): Promise<void> {
+  await ctx.program.methods
+    .updateConfig(config)
+    .accounts({
+      ido: ctx.ido,
+      admin: admin.publicKey,
+    })
+    .signers([admin])
+    .rpc();
+}
+
+export async function proposeAuthority(
+  ctx: Context,
+  admin: Keypair,
+  newAuthority: PublicKey
+): Promise<void> {
+  await ctx.program.methods
+    .proposeAuthority(newAuthority)
+    .accounts({
+      ido: ctx.ido,
+      admin: admin.publicKey,
+    })
+    .signers([admin])
+    .rpc();
+}
+
+export async function acceptAuthority(
+  ctx: Context,
+  newAuthority: Keypair
+): Promise<void> {
+  await ctx.program.methods
+    .acceptAuthority()
+    .accounts({
+      ido: ctx.ido,
+      newAuthority: newAuthority.publicKey,
     })
-    .signers([ctx.idoAuthority])
+    .signers([newAuthority])
     .rpc();
 }
@@ -1,5 +1,7 @@
-import * as anchor from "@project-serum/anchor";
-import { BN, Program } from "@project-serum/anchor";
+import * as anchor from "@coral-xyz/anchor";
+import { createHash } from "crypto";
+import * as fs from "fs";
+import { BN, Program } from "@coral-xyz/anchor";
 import {
   Connection,
   Keypair,
@@ -7,8 +9,15 @@ import {
   sendAndConfirmTransaction,
   Transaction,
 } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { Ido } from "../target/types/ido";
-import { createMint, findATA, getTokenMetadata, TokenAccount } from "./token";
+import {
+  createMint,
+  createMintWithTransferFee,
+  findATA,
+  getTokenMetadata,
+  TokenAccount,
+} from "./token";
 import { airdrop, findPDA } from "./utils";
 import { createCreateMetadataAccountV2Instruction } from "@metaplex-foundation/mpl-token-metadata";
 
@@ -21,17 +30,25 @@ export class Context {
   acdmMintAuthority: Keypair;
   usdcMint: PublicKey;
   usdcMintAuthority: Keypair;
+  acdmTokenProgram: PublicKey;
+  usdcTokenProgram: PublicKey;
+  // if set, USDC is a Token-2022 mint withholding this fee from every transfer
+  usdcTransferFeeBps: number | null;
 
   ido: PublicKey;
-  idoAuthority: Keypair;
+  admin: Keypair;
+  operator: Keypair;
+  treasurer: Keypair;
   idoAcdm: TokenAccount;
   idoUsdc: TokenAccount;
+  referralUsdc: TokenAccount;
 
   user1: Keypair;
   user2: Keypair;
   user3: Keypair;
+  user4: Keypair;
 
-  constructor() {
+  constructor(usdcTransferFeeBps: number | null = null) {
     const provider = anchor.AnchorProvider.env();
     anchor.setProvider(provider);
     this.connection = provider.connection;
@@ -39,23 +56,40 @@ export class Context {
     this.payer = new Keypair();
     this.acdmMintAuthority = new Keypair();
     this.usdcMintAuthority = new Keypair();
-    this.idoAuthority = new Keypair();
+    this.admin = new Keypair();
+    this.operator = new Keypair();
+    this.treasurer = new Keypair();
     this.user1 = new Keypair();
     this.user2 = new Keypair();
     this.user3 = new Keypair();
+    this.user4 = new Keypair();
+    this.acdmTokenProgram = TOKEN_PROGRAM_ID;
+    this.usdcTransferFeeBps = usdcTransferFeeBps;
+    this.usdcTokenProgram =
+      usdcTransferFeeBps === null ? TOKEN_PROGRAM_ID : TOKEN_2022_PROGRAM_ID;
   }
 
   async setup() {
     await airdrop(this, [
-      this.idoAuthority.publicKey,
+      this.admin.publicKey,
+      this.operator.publicKey,
       this.acdmMintAuthority.publicKey,
       this.user1.publicKey,
       this.user2.publicKey,
       this.user3.publicKey,
+      this.user4.publicKey,
     ]);
 
     this.acdmMint = await createMint(this, this.acdmMintAuthority, 2);
-    this.usdcMint = await createMint(this, this.usdcMintAuthority, 6);
+    this.usdcMint =
+      this.usdcTransferFeeBps === null
+        ? await createMint(this, this.usdcMintAuthority, 6)
+        : await createMintWithTransferFee(
+            this,
+            this.usdcMintAuthority,
+            6,
+            this.usdcTransferFeeBps
+          );
 
     const acdmMetadata = await getTokenMetadata(this.acdmMint);
 
@@ -87,18 +121,65 @@ export class Context {
       this.acdmMintAuthority,
     ]);
 
-    this.ido = await findPDA(this, [Buffer.from("ido")]);
+    await this.findIdoAccounts();
+  }
+
+  // takes the keys and mints of the IDO of the first release,
+  // whose accounts are loaded into the validator from tests/fixtures/legacy
+  async setupLegacy() {
+    const keys = JSON.parse(
+      fs.readFileSync("tests/fixtures/legacy/keys.json", "utf8")
+    );
+    const keypair = (name: string) =>
+      Keypair.fromSecretKey(Uint8Array.from(keys[name]));
+
+    this.admin = keypair("admin");
+    this.acdmMintAuthority = keypair("acdmMintAuthority");
+    this.usdcMintAuthority = keypair("usdcMintAuthority");
+    this.user1 = keypair("user1");
+    this.user2 = keypair("user2");
+    this.user3 = keypair("user3");
+    this.acdmMint = keypair("acdmMint").publicKey;
+    this.usdcMint = keypair("usdcMint").publicKey;
+
+    await airdrop(this, [this.admin.publicKey]);
+    await this.findIdoAccounts();
+  }
+
+  async findIdoAccounts() {
+    this.ido = await findPDA(this, [
+      Buffer.from("ido"),
+      this.acdmMint.toBuffer(),
+    ]);
     this.idoAcdm = await this.acdmATA(this.ido);
     this.idoUsdc = await this.usdcATA(this.ido);
+    this.referralUsdc = new TokenAccount(
+      await findPDA(this, [Buffer.from("referral_usdc"), this.ido.toBuffer()]),
+      this.usdcMint,
+      this.usdcTokenProgram
+    );
   }
 
   async member(user: PublicKey): Promise<PublicKey> {
-    return await findPDA(this, [Buffer.from("member"), user.toBuffer()]);
+    return await findPDA(this, [
+      Buffer.from("member"),
+      this.ido.toBuffer(),
+      user.toBuffer(),
+    ]);
+  }
+
+  async referralCode(code: string): Promise<PublicKey> {
+    return await findPDA(this, [
+      Buffer.from("code"),
+      this.ido.toBuffer(),
+      createHash("sha256").update(code).digest(),
+    ]);
   }
 
   async order(id: BN): Promise<PublicKey> {
     return await findPDA(this, [
       Buffer.from("order"),
+      this.ido.toBuffer(),
       id.toArrayLike(Buffer, "le", 8),
     ]);
   }
@@ -107,11 +188,23 @@ export class Context {
     return this.acdmATA(await this.order(id));
   }
 
+  async bid(id: BN): Promise<PublicKey> {
+    return await findPDA(this, [
+      Buffer.from("bid"),
+      this.ido.toBuffer(),
+      id.toArrayLike(Buffer, "le", 8),
+    ]);
+  }
+
+  async bidUsdc(id: BN): Promise<TokenAccount> {
+    return this.usdcATA(await this.bid(id));
+  }
+
   async acdmATA(owner: PublicKey): Promise<TokenAccount> {
-    return await findATA(this, owner, this.acdmMint);
+    return await findATA(this, owner, this.acdmMint, this.acdmTokenProgram);
   }
 
   async usdcATA(owner: PublicKey): Promise<TokenAccount> {
-    return await findATA(this, owner, this.usdcMint);
+    return await findATA(this, owner, this.usdcMint, this.usdcTokenProgram);
   }
 }
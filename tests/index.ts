@@ -1,21 +1,46 @@
-import { BN } from "@project-serum/anchor";
+import { BN } from "@coral-xyz/anchor";
 import { expect } from "chai";
 import * as chai from "chai";
 import chaiAsPromised from "chai-as-promised";
+import { Keypair } from "@solana/web3.js";
+import { getMint, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import { Context } from "./ctx";
 import {
+  acceptAuthority,
+  addBid,
   addOrder,
   buyAcdm,
+  claimReferralRewards,
+  closeExpiredOrder,
+  crank,
+  createReferralCode,
   endIdo,
+  fillBid,
+  IdoConfig,
   initialize,
+  initializeWithMint,
+  matchOrders,
+  migrateIdo,
+  migrateMember,
+  migrateOrder,
+  pause,
+  proposeAuthority,
   redeemOrder,
+  redeemOrders,
   registerMember,
+  registerMemberWithCode,
+  removeBid,
   removeOrder,
+  setRoles,
   startSaleRound,
   startTradeRound,
+  unpause,
+  updateConfig,
+  updateOrder,
   withdrawIdoUsdc,
 } from "./api";
 import { burnAll, mintTo } from "./token";
+import { airdrop, findPDA, sleep } from "./utils";
 
 chai.use(chaiAsPromised);
 
@@ -23,6 +48,40 @@ const ctx = new Context();
 
 const INITIAL_ISSUE = 10_000;
 const INITIAL_PRICE = 100_000;
+const ROUND_TIME = 2;
+const ACDM_DECIMALS = 2;
+
+const config: IdoConfig = {
+  initialIssue: new BN(INITIAL_ISSUE),
+  initialPrice: new BN(INITIAL_PRICE),
+  priceCurve: {
+    geometric: {
+      numerator: new BN(103),
+      denominator: new BN(100),
+      increment: new BN((INITIAL_PRICE * 2) / 5),
+    },
+  },
+  fees: {
+    saleRefererBps: [500, 300],
+    tradeFeeBps: 500,
+    tradeRefererBps: [250, 250],
+  },
+};
+
+// prices are in USDC base units per whole ACDM
+function usdc(acdmAmount: number, price: number): number {
+  return (acdmAmount * price) / 10 ** ACDM_DECIMALS;
+}
+
+async function fetchMember(user: Keypair) {
+  return await ctx.program.account.member.fetch(
+    await ctx.member(user.publicKey)
+  );
+}
+
+async function rewards(user: Keypair): Promise<number> {
+  return (await fetchMember(user)).rewards.toNumber();
+}
 
 describe("setup", () => {
   it("setups", async () => {
@@ -32,19 +91,114 @@ describe("setup", () => {
 
 describe("instructions", () => {
   it("initialize", async () => {
-    const roundTime = 2;
+    await expect(
+      initialize(ctx, ROUND_TIME, { ...config, initialPrice: new BN(0) })
+    ).to.be.rejectedWith("InvalidConfig");
+    await expect(
+      initialize(ctx, ROUND_TIME, {
+        ...config,
+        priceCurve: { table: { prices: [] } },
+      })
+    ).to.be.rejectedWith("InvalidConfig");
+    await expect(
+      initialize(ctx, ROUND_TIME, {
+        ...config,
+        fees: { ...config.fees, saleRefererBps: [6_000, 5_000] },
+      })
+    ).to.be.rejectedWith("InvalidConfig");
+    await expect(
+      initialize(ctx, ROUND_TIME, {
+        ...config,
+        fees: { ...config.fees, tradeRefererBps: [500, 500] },
+      })
+    ).to.be.rejectedWith("InvalidConfig");
 
-    await initialize(ctx, roundTime);
+    await initialize(ctx, ROUND_TIME, config);
 
     const ido = await ctx.program.account.ido.fetch(ctx.ido);
     expect(ido.bump).to.be.above(200);
-    expect(ido.authority).to.eql(ctx.idoAuthority.publicKey);
+    expect(ido.admin).to.eql(ctx.admin.publicKey);
+    expect(ido.operator).to.eql(ctx.admin.publicKey);
+    expect(ido.treasurer).to.eql(ctx.admin.publicKey);
     expect(ido.state).to.eql({ notStarted: {} });
     expect(ido.acdmMint).to.eql(ctx.acdmMint);
     expect(ido.usdcMint).to.eql(ctx.usdcMint);
-    expect(ido.usdcTraded.toNumber()).to.eql(INITIAL_ISSUE * INITIAL_PRICE);
-    expect(ido.roundTime).to.eql(roundTime);
-    expect(ido.currentStateStartTs).to.not.eql(0);
+    expect(ido.acdmDecimals).to.eql(ACDM_DECIMALS);
+    expect(ido.usdcTraded.toNumber()).to.eql(usdc(INITIAL_ISSUE, INITIAL_PRICE));
+    expect(ido.roundTime.toNumber()).to.eql(ROUND_TIME);
+    expect(ido.currentStateStartTs.toNumber()).to.not.eql(0);
+    expect(ido.config.initialIssue.toNumber()).to.eql(INITIAL_ISSUE);
+    expect(ido.config.initialPrice.toNumber()).to.eql(INITIAL_PRICE);
+    expect(ido.config.priceCurve.geometric.numerator.toNumber()).to.eql(103);
+
+    const acdmMint = await getMint(ctx.connection, ctx.acdmMint);
+    expect(acdmMint.mintAuthority).to.eql(ctx.ido);
+  });
+
+  it("initializeWithMint", async () => {
+    const acdmMintKeypair = new Keypair();
+    const ido = await initializeWithMint(
+      ctx,
+      acdmMintKeypair,
+      ROUND_TIME,
+      // referral depths may differ between round types
+      { ...config, fees: { ...config.fees, tradeRefererBps: [250] } },
+      2,
+      // the new ACDM mint may belong to Token-2022
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const idoAccount = await ctx.program.account.ido.fetch(ido);
+    expect(idoAccount.acdmMint).to.eql(acdmMintKeypair.publicKey);
+    expect(idoAccount.state).to.eql({ notStarted: {} });
+    expect(idoAccount.config.fees.tradeRefererBps).to.eql([250]);
+
+    const acdmMint = await getMint(
+      ctx.connection,
+      acdmMintKeypair.publicKey,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(acdmMint.mintAuthority).to.eql(ido);
+    expect(acdmMint.decimals).to.eql(2);
+
+    const acdmMintInfo = await ctx.connection.getAccountInfo(
+      acdmMintKeypair.publicKey
+    );
+    expect(acdmMintInfo.owner).to.eql(TOKEN_2022_PROGRAM_ID);
+  });
+
+  it("setRoles", async () => {
+    await setRoles(ctx, ctx.operator.publicKey, ctx.treasurer.publicKey);
+
+    const ido = await ctx.program.account.ido.fetch(ctx.ido);
+    expect(ido.operator).to.eql(ctx.operator.publicKey);
+    expect(ido.treasurer).to.eql(ctx.treasurer.publicKey);
+  });
+
+  it("updateConfig", async () => {
+    await expect(updateConfig(ctx, ctx.operator, config)).to.be.rejectedWith(
+      "ConstraintAddress"
+    );
+    await expect(
+      updateConfig(ctx, ctx.admin, { ...config, initialPrice: new BN(0) })
+    ).to.be.rejectedWith("InvalidConfig");
+
+    await updateConfig(ctx, ctx.admin, {
+      ...config,
+      initialIssue: new BN(2 * INITIAL_ISSUE),
+    });
+
+    let ido = await ctx.program.account.ido.fetch(ctx.ido);
+    expect(ido.config.initialIssue.toNumber()).to.eql(2 * INITIAL_ISSUE);
+    expect(ido.usdcTraded.toNumber()).to.eql(
+      usdc(2 * INITIAL_ISSUE, INITIAL_PRICE)
+    );
+
+    await updateConfig(ctx, ctx.admin, config);
+
+    ido = await ctx.program.account.ido.fetch(ctx.ido);
+    expect(ido.usdcTraded.toNumber()).to.eql(usdc(INITIAL_ISSUE, INITIAL_PRICE));
   });
 
   it("registerMember", async () => {
@@ -64,6 +218,10 @@ describe("instructions", () => {
     expect(member2.bump).to.be.above(200);
     expect(member2.referer).to.eql(ctx.user3.publicKey);
 
+    await expect(
+      registerMember(ctx, ctx.user1, ctx.user1.publicKey)
+    ).to.be.rejectedWith("SelfReferral");
+
     await registerMember(ctx, ctx.user1, ctx.user2.publicKey);
 
     const member1 = await ctx.program.account.member.fetch(
@@ -71,6 +229,35 @@ describe("instructions", () => {
     );
     expect(member1.bump).to.be.above(200);
     expect(member1.referer).to.eql(ctx.user2.publicKey);
+
+    expect((await fetchMember(ctx.user2)).referees).to.eql(1);
+    expect((await fetchMember(ctx.user3)).referees).to.eql(1);
+  });
+
+  it("registerMemberWithCode", async () => {
+    const code = "ACADEMY";
+
+    await expect(createReferralCode(ctx, ctx.user3, "")).to.be.rejectedWith(
+      "InvalidReferralCode"
+    );
+    // longer than a seed can be, which is why codes are hashed into it
+    await expect(
+      createReferralCode(ctx, ctx.user3, "A".repeat(33))
+    ).to.be.rejectedWith("InvalidReferralCode");
+    await createReferralCode(ctx, ctx.user3, code);
+
+    const referralCode = await ctx.program.account.referralCode.fetch(
+      await ctx.referralCode(code)
+    );
+    expect(referralCode.bump).to.be.above(200);
+    expect(referralCode.authority).to.eql(ctx.user3.publicKey);
+
+    await registerMemberWithCode(ctx, ctx.user4, code);
+
+    expect((await fetchMember(ctx.user4)).referer).to.eql(
+      ctx.user3.publicKey
+    );
+    expect((await fetchMember(ctx.user3)).referees).to.eql(2);
   });
 
   it("startSaleRound", async () => {
@@ -78,24 +265,58 @@ describe("instructions", () => {
 
     const ido = await ctx.program.account.ido.fetch(ctx.ido);
     expect(ido.state).to.eql({ saleRound: {} });
-    expect(ido.currentStateStartTs).to.not.eql(0);
+    expect(ido.currentStateStartTs.toNumber()).to.not.eql(0);
     expect(ido.acdmPrice.toNumber()).to.eql(INITIAL_PRICE);
     expect(ido.saleRoundsStarted).to.eql(1);
     expect(await ctx.idoAcdm.amount(ctx)).to.eql(INITIAL_ISSUE);
+
+    await expect(crank(ctx)).to.be.rejectedWith("CannotEndRound");
+  });
+
+  it("pause", async () => {
+    await pause(ctx);
+
+    const ido = await ctx.program.account.ido.fetch(ctx.ido);
+    expect(ido.paused).to.eql(true);
+    expect(ido.pausedAt.toNumber()).to.not.eql(0);
+
+    await expect(pause(ctx)).to.be.rejectedWith("Paused");
+    await expect(buyAcdm(ctx, new BN(1), ctx.user1)).to.be.rejectedWith(
+      "Paused"
+    );
+
+    // taking funds out stays possible
+    await claimReferralRewards(ctx, ctx.user3);
+  });
+
+  it("unpause", async () => {
+    const currentStateStartTs = (
+      await ctx.program.account.ido.fetch(ctx.ido)
+    ).currentStateStartTs.toNumber();
+
+    await unpause(ctx);
+
+    const ido = await ctx.program.account.ido.fetch(ctx.ido);
+    expect(ido.paused).to.eql(false);
+    expect(ido.currentStateStartTs.toNumber()).to.be.at.least(
+      currentStateStartTs
+    );
+
+    await expect(unpause(ctx)).to.be.rejectedWith("NotPaused");
   });
 
   let buyAmount = 500;
 
   it("buyAcdm", async () => {
     await expect(
-      buyAcdm(ctx, new BN(9_000_000_000_000_000), ctx.user1)
+      buyAcdm(ctx, new BN("18446744073709551615"), ctx.user1)
     ).to.be.rejectedWith("Overflow");
 
     await mintTo(
       ctx,
       await ctx.usdcATA(ctx.user1.publicKey),
       ctx.usdcMintAuthority,
-      buyAmount * INITIAL_PRICE
+      usdc(buyAmount, INITIAL_PRICE)
     );
     await buyAcdm(ctx, new BN(buyAmount), ctx.user1);
     expect(await (await ctx.acdmATA(ctx.user1.publicKey)).amount(ctx)).to.eql(
@@ -104,23 +325,21 @@ describe("instructions", () => {
     expect(await (await ctx.usdcATA(ctx.user1.publicKey)).amount(ctx)).to.eql(
       0
     );
-    expect(await (await ctx.usdcATA(ctx.user2.publicKey)).amount(ctx)).to.eql(
-      (buyAmount * INITIAL_PRICE * 5) / 100
+    expect(await rewards(ctx.user2)).to.eql(
+      (usdc(buyAmount, INITIAL_PRICE) * 5) / 100
     );
-    await burnAll(ctx, await ctx.usdcATA(ctx.user2.publicKey), ctx.user2);
-    expect(await (await ctx.usdcATA(ctx.user3.publicKey)).amount(ctx)).to.eql(
-      (buyAmount * INITIAL_PRICE * 3) / 100
+    expect(await rewards(ctx.user3)).to.eql(
+      (usdc(buyAmount, INITIAL_PRICE) * 3) / 100
     );
-    await burnAll(ctx, await ctx.usdcATA(ctx.user3.publicKey), ctx.user3);
     expect(await ctx.idoUsdc.amount(ctx)).to.eql(
-      (buyAmount * INITIAL_PRICE * 92) / 100
+      (usdc(buyAmount, INITIAL_PRICE) * 92) / 100
     );
 
     await mintTo(
       ctx,
       await ctx.usdcATA(ctx.user2.publicKey),
       ctx.usdcMintAuthority,
-      buyAmount * INITIAL_PRICE
+      usdc(buyAmount, INITIAL_PRICE)
     );
     await buyAcdm(ctx, new BN(buyAmount), ctx.user2);
     expect(await (await ctx.acdmATA(ctx.user2.publicKey)).amount(ctx)).to.eql(
@@ -130,20 +349,19 @@ describe("instructions", () => {
     expect(await (await ctx.usdcATA(ctx.user2.publicKey)).amount(ctx)).to.eql(
       0
     );
-    expect(await (await ctx.usdcATA(ctx.user3.publicKey)).amount(ctx)).to.eql(
-      (buyAmount * INITIAL_PRICE * 5) / 100
+    expect(await rewards(ctx.user3)).to.eql(
+      (usdc(buyAmount, INITIAL_PRICE) * 8) / 100
     );
-    await burnAll(ctx, await ctx.usdcATA(ctx.user3.publicKey), ctx.user3);
     expect(await ctx.idoUsdc.amount(ctx)).to.eql(
-      (buyAmount * INITIAL_PRICE * 92) / 100 +
-        (buyAmount * INITIAL_PRICE * 95) / 100
+      (usdc(buyAmount, INITIAL_PRICE) * 92) / 100 +
+        (usdc(buyAmount, INITIAL_PRICE) * 95) / 100
     );
 
     await mintTo(
       ctx,
       await ctx.usdcATA(ctx.user3.publicKey),
       ctx.usdcMintAuthority,
-      buyAmount * INITIAL_PRICE
+      usdc(buyAmount, INITIAL_PRICE)
     );
     await buyAcdm(ctx, new BN(buyAmount), ctx.user3);
     expect(await (await ctx.acdmATA(ctx.user3.publicKey)).amount(ctx)).to.eql(
@@ -153,9 +371,21 @@ describe("instructions", () => {
       0
     );
     expect(await ctx.idoUsdc.amount(ctx)).to.eql(
-      (buyAmount * INITIAL_PRICE * 92) / 100 +
-        (buyAmount * INITIAL_PRICE * 95) / 100 +
-        buyAmount * INITIAL_PRICE
+      (usdc(buyAmount, INITIAL_PRICE) * 92) / 100 +
+        (usdc(buyAmount, INITIAL_PRICE) * 95) / 100 +
+        usdc(buyAmount, INITIAL_PRICE)
+    );
+    expect(await ctx.referralUsdc.amount(ctx)).to.eql(
+      (usdc(buyAmount, INITIAL_PRICE) * 13) / 100
+    );
+
+    const member3 = await fetchMember(ctx.user3);
+    expect(member3.acdmBought.toNumber()).to.eql(buyAmount);
+    expect(member3.rewardsEarned[0].toNumber()).to.eql(
+      (usdc(buyAmount, INITIAL_PRICE) * 5) / 100
+    );
+    expect(member3.rewardsEarned[1].toNumber()).to.eql(
+      (usdc(buyAmount, INITIAL_PRICE) * 3) / 100
     );
   });
 
@@ -164,7 +394,7 @@ describe("instructions", () => {
 
     const ido = await ctx.program.account.ido.fetch(ctx.ido);
     expect(ido.state).to.eql({ tradeRound: {} });
-    expect(ido.currentStateStartTs).to.not.eql(0);
+    expect(ido.currentStateStartTs.toNumber()).to.not.eql(0);
     expect(ido.usdcTraded.toNumber()).to.eql(0);
 
     expect(await ctx.idoAcdm.amount(ctx)).to.eql(0);
@@ -194,11 +424,48 @@ describe("instructions", () => {
     expect(order.bump).to.be.above(200);
     expect(order.authority).to.eql(ctx.user1.publicKey);
     expect(order.price.toNumber()).to.eql(orderPrice);
+    expect(order.originalAmount.toNumber()).to.eql(orderAmount);
+    expect(order.filledAmount.toNumber()).to.eql(0);
+    expect(order.createdTs.toNumber()).to.not.eql(0);
+    expect(order.round).to.eql(1);
+
+    // fields are added out of the reserved space, so the size of orders never changes
+    const orderInfo = await ctx.connection.getAccountInfo(
+      await ctx.order(orderId)
+    );
+    expect(orderInfo.data.length).to.eql(8 + 1 + 1 + 32 + 8 + 64);
 
     const ido = await ctx.program.account.ido.fetch(ctx.ido);
     expect(ido.orders.toNumber()).to.eql(1);
   });
 
+  let expiringOrderId: BN;
+
+  it("addOrder with expiry", async () => {
+    await expect(
+      addOrder(ctx, new BN(50), new BN(orderPrice), ctx.user3, {
+        at: { ts: new BN(1) },
+      })
+    ).to.be.rejectedWith("InvalidOrderExpiry");
+
+    expiringOrderId = await addOrder(
+      ctx,
+      new BN(50),
+      new BN(orderPrice),
+      ctx.user3,
+      { endOfRound: {} }
+    );
+
+    const order = await ctx.program.account.order.fetch(
+      await ctx.order(expiringOrderId)
+    );
+    expect(order.expiry).to.eql({ endOfRound: {} });
+
+    await expect(closeExpiredOrder(ctx, expiringOrderId)).to.be.rejectedWith(
+      "OrderNotExpired"
+    );
+  });
+
   const redeemAmount = 40;
 
   it("redeemOrder", async () => {
@@ -206,7 +473,7 @@ describe("instructions", () => {
       ctx,
       await ctx.usdcATA(ctx.user2.publicKey),
       ctx.usdcMintAuthority,
-      redeemAmount * orderPrice
+      usdc(redeemAmount, orderPrice)
     );
     await redeemOrder(ctx, orderId, new BN(redeemAmount), ctx.user2);
 
@@ -214,20 +481,317 @@ describe("instructions", () => {
       redeemAmount
     );
     expect(await (await ctx.usdcATA(ctx.user1.publicKey)).amount(ctx)).to.eql(
-      (redeemAmount * orderPrice * 95) / 100
+      (usdc(redeemAmount, orderPrice) * 95) / 100
     );
     expect(await (await ctx.usdcATA(ctx.user2.publicKey)).amount(ctx)).to.eql(
-      (redeemAmount * orderPrice * 25) / 1000
+      0
     );
-    expect(await (await ctx.usdcATA(ctx.user3.publicKey)).amount(ctx)).to.eql(
-      (redeemAmount * orderPrice * 25) / 1000
+    expect(await rewards(ctx.user2)).to.eql(
+      (usdc(buyAmount, INITIAL_PRICE) * 5) / 100 +
+        (usdc(redeemAmount, orderPrice) * 25) / 1000
+    );
+    expect(await rewards(ctx.user3)).to.eql(
+      (usdc(buyAmount, INITIAL_PRICE) * 8) / 100 +
+        (usdc(redeemAmount, orderPrice) * 25) / 1000
     );
     expect(await (await ctx.orderAcdm(orderId)).amount(ctx)).to.eql(
       orderAmount - redeemAmount
     );
 
+    const order = await ctx.program.account.order.fetch(
+      await ctx.order(orderId)
+    );
+    expect(order.originalAmount.toNumber()).to.eql(orderAmount);
+    expect(order.filledAmount.toNumber()).to.eql(redeemAmount);
+
     const ido = await ctx.program.account.ido.fetch(ctx.ido);
-    expect(ido.usdcTraded.toNumber()).to.eql(redeemAmount * orderPrice);
+    expect(ido.usdcTraded.toNumber()).to.eql(usdc(redeemAmount, orderPrice));
+
+    expect((await fetchMember(ctx.user1)).acdmSold.toNumber()).to.eql(
+      redeemAmount
+    );
+    expect((await fetchMember(ctx.user2)).acdmBought.toNumber()).to.eql(
+      buyAmount + redeemAmount
+    );
+  });
+
+  it("claimReferralRewards", async () => {
+    const claimed = await rewards(ctx.user3);
+
+    await claimReferralRewards(ctx, ctx.user3);
+
+    expect(await rewards(ctx.user3)).to.eql(0);
+    expect(await (await ctx.usdcATA(ctx.user3.publicKey)).amount(ctx)).to.eql(
+      claimed
+    );
+  });
+
+  let bidId: BN;
+  const bidAmount = 20;
+  const bidPrice = 120_000;
+  const fillAmount = 10;
+
+  it("addBid", async () => {
+    const nonMember = new Keypair();
+    await airdrop(ctx, [nonMember.publicKey]);
+    await expect(
+      addBid(ctx, new BN(bidAmount), new BN(bidPrice), nonMember)
+    ).to.be.rejectedWith("AccountNotInitialized");
+
+    await mintTo(
+      ctx,
+      await ctx.usdcATA(ctx.user3.publicKey),
+      ctx.usdcMintAuthority,
+      usdc(bidAmount, bidPrice)
+    );
+    bidId = await addBid(ctx, new BN(bidAmount), new BN(bidPrice), ctx.user3);
+
+    expect(await (await ctx.bidUsdc(bidId)).amount(ctx)).to.eql(
+      usdc(bidAmount, bidPrice)
+    );
+
+    const bid = await ctx.program.account.bid.fetch(await ctx.bid(bidId));
+    expect(bid.authority).to.eql(ctx.user3.publicKey);
+    expect(bid.price.toNumber()).to.eql(bidPrice);
+    expect(bid.originalAmount.toNumber()).to.eql(bidAmount);
+    expect(bid.filledAmount.toNumber()).to.eql(0);
+  });
+
+  it("fillBid", async () => {
+    const acdmBought = (await fetchMember(ctx.user3)).acdmBought.toNumber();
+
+    await fillBid(ctx, bidId, new BN(fillAmount), ctx.user2);
+
+    expect(await (await ctx.acdmATA(ctx.user2.publicKey)).amount(ctx)).to.eql(
+      redeemAmount - fillAmount
+    );
+    expect(await (await ctx.usdcATA(ctx.user2.publicKey)).amount(ctx)).to.eql(
+      (usdc(fillAmount, bidPrice) * 95) / 100
+    );
+    expect(await (await ctx.bidUsdc(bidId)).amount(ctx)).to.eql(
+      usdc(bidAmount - fillAmount, bidPrice)
+    );
+    expect(await rewards(ctx.user3)).to.eql(
+      (usdc(fillAmount, bidPrice) * 25) / 1000
+    );
+    expect((await fetchMember(ctx.user3)).acdmBought.toNumber()).to.eql(
+      acdmBought + fillAmount
+    );
+
+    const bid = await ctx.program.account.bid.fetch(await ctx.bid(bidId));
+    expect(bid.filledAmount.toNumber()).to.eql(fillAmount);
+
+    await expect(
+      fillBid(ctx, bidId, new BN(bidAmount - fillAmount + 1), ctx.user2)
+    ).to.be.rejectedWith("BidAmountExceeded");
+  });
+
+  it("removeBid", async () => {
+    const usdcBefore = await (
+      await ctx.usdcATA(ctx.user3.publicKey)
+    ).amount(ctx);
+
+    await removeBid(ctx, bidId, ctx.user3);
+
+    expect(await (await ctx.usdcATA(ctx.user3.publicKey)).amount(ctx)).to.eql(
+      usdcBefore + usdc(bidAmount - fillAmount, bidPrice)
+    );
+    expect(
+      await ctx.program.account.bid.fetchNullable(await ctx.bid(bidId))
+    ).to.eql(null);
+  });
+
+  const matchAmount = 10;
+
+  it("matchOrders", async () => {
+    await mintTo(
+      ctx,
+      await ctx.usdcATA(ctx.user3.publicKey),
+      ctx.usdcMintAuthority,
+      usdc(matchAmount, bidPrice)
+    );
+    const matchBidId = await addBid(
+      ctx,
+      new BN(matchAmount),
+      new BN(bidPrice),
+      ctx.user3
+    );
+
+    await expect(matchOrders(ctx, orderId, matchBidId)).to.be.rejectedWith(
+      "OrdersDontCross"
+    );
+
+    const usdcBefore = await (
+      await ctx.usdcATA(ctx.user2.publicKey)
+    ).amount(ctx);
+    const askId = await addOrder(
+      ctx,
+      new BN(matchAmount),
+      new BN(bidPrice - 10_000),
+      ctx.user2
+    );
+
+    await matchOrders(ctx, askId, matchBidId);
+
+    // the bid has rested at its price the longest, so it sets the price
+    expect(await (await ctx.usdcATA(ctx.user2.publicKey)).amount(ctx)).to.eql(
+      usdcBefore + (usdc(matchAmount, bidPrice) * 95) / 100
+    );
+    expect(await (await ctx.orderAcdm(askId)).amount(ctx)).to.eql(0);
+    expect(await (await ctx.bidUsdc(matchBidId)).amount(ctx)).to.eql(0);
+
+    const ask = await ctx.program.account.order.fetch(await ctx.order(askId));
+    expect(ask.filledAmount.toNumber()).to.eql(matchAmount);
+    const bid = await ctx.program.account.bid.fetch(
+      await ctx.bid(matchBidId)
+    );
+    expect(bid.filledAmount.toNumber()).to.eql(matchAmount);
+
+    await expect(matchOrders(ctx, askId, matchBidId)).to.be.rejectedWith(
+      "NothingToMatch"
+    );
+  });
+
+  const sweepAmount = 12;
+
+  it("redeemOrders", async () => {
+    const cheapOrderId = await addOrder(
+      ctx,
+      new BN(5),
+      new BN(125_000),
+      ctx.user2
+    );
+    const dearOrderId = await addOrder(
+      ctx,
+      new BN(10),
+      new BN(128_000),
+      ctx.user2
+    );
+    const usdcAmount = usdc(5, 125_000) + usdc(sweepAmount - 5, 128_000);
+    await mintTo(
+      ctx,
+      await ctx.usdcATA(ctx.user3.publicKey),
+      ctx.usdcMintAuthority,
+      usdcAmount
+    );
+
+    await expect(
+      redeemOrders(
+        ctx,
+        [dearOrderId, cheapOrderId],
+        new BN(sweepAmount),
+        new BN(130_000),
+        ctx.user3
+      )
+    ).to.be.rejectedWith("OrdersNotSorted");
+    await expect(
+      redeemOrders(
+        ctx,
+        [cheapOrderId, dearOrderId],
+        new BN(sweepAmount),
+        new BN(126_000),
+        ctx.user3
+      )
+    ).to.be.rejectedWith("PriceLimitExceeded");
+
+    const acdmBefore = await (
+      await ctx.acdmATA(ctx.user3.publicKey)
+    ).amount(ctx);
+    const usdcBefore = await (
+      await ctx.usdcATA(ctx.user3.publicKey)
+    ).amount(ctx);
+    const usdcTradedBefore = (
+      await ctx.program.account.ido.fetch(ctx.ido)
+    ).usdcTraded.toNumber();
+
+    await redeemOrders(
+      ctx,
+      [cheapOrderId, dearOrderId],
+      new BN(sweepAmount),
+      new BN(130_000),
+      ctx.user3
+    );
+
+    expect(await (await ctx.acdmATA(ctx.user3.publicKey)).amount(ctx)).to.eql(
+      acdmBefore + sweepAmount
+    );
+    expect(await (await ctx.usdcATA(ctx.user3.publicKey)).amount(ctx)).to.eql(
+      usdcBefore - usdcAmount
+    );
+    expect(await (await ctx.orderAcdm(cheapOrderId)).amount(ctx)).to.eql(0);
+    expect(await (await ctx.orderAcdm(dearOrderId)).amount(ctx)).to.eql(
+      10 - (sweepAmount - 5)
+    );
+    expect(
+      (await ctx.program.account.ido.fetch(ctx.ido)).usdcTraded.toNumber()
+    ).to.eql(usdcTradedBefore + usdcAmount);
+  });
+
+  it("migrate", async () => {
+    expect((await ctx.program.account.ido.fetch(ctx.ido)).version).to.eql(1);
+    expect(
+      (await ctx.program.account.order.fetch(await ctx.order(orderId))).version
+    ).to.eql(1);
+    expect((await fetchMember(ctx.user1)).version).to.eql(1);
+    expect(
+      (
+        await ctx.program.account.referralCode.fetch(
+          await ctx.referralCode("ACADEMY")
+        )
+      ).version
+    ).to.eql(1);
+
+    // the IDO of the first release is not the one of this context
+    await expect(migrateIdo(ctx, config)).to.be.rejectedWith(
+      "AccountNotInitialized"
+    );
+    await expect(migrateOrder(ctx, orderId)).to.be.rejectedWith(
+      "AccountNotInitialized"
+    );
+    await expect(
+      migrateMember(ctx, new Keypair().publicKey)
+    ).to.be.rejectedWith("NotMigrated");
+  });
+
+  it("updateOrder", async () => {
+    await expect(
+      updateOrder(ctx, orderId, new BN(1), new BN(0), new BN(0), ctx.user2)
+    ).to.be.rejectedWith("ConstraintAddress");
+
+    const sequenceBefore = (
+      await ctx.program.account.order.fetch(await ctx.order(orderId))
+    ).sequence.toNumber();
+
+    orderPrice = 140_000;
+    await updateOrder(
+      ctx,
+      orderId,
+      new BN(orderPrice),
+      new BN(10),
+      new BN(0),
+      ctx.user1
+    );
+    expect(await (await ctx.orderAcdm(orderId)).amount(ctx)).to.eql(
+      orderAmount - redeemAmount + 10
+    );
+
+    await updateOrder(ctx, orderId, null, new BN(0), new BN(20), ctx.user1);
+    expect(await (await ctx.orderAcdm(orderId)).amount(ctx)).to.eql(
+      orderAmount - redeemAmount - 10
+    );
+
+    const order = await ctx.program.account.order.fetch(
+      await ctx.order(orderId)
+    );
+    expect(order.price.toNumber()).to.eql(orderPrice);
+    expect(order.originalAmount.toNumber()).to.eql(orderAmount - 10);
+    expect(order.filledAmount.toNumber()).to.eql(redeemAmount);
+
+    // repricing moves the order behind everything resting on the book,
+    // while changing only its amount doesn't
+    const ido = await ctx.program.account.ido.fetch(ctx.ido);
+    expect(order.sequence.toNumber()).to.be.above(sequenceBefore);
+    expect(order.sequence.toNumber()).to.eql(ido.sequence.toNumber() - 1);
   });
 
   it("removeOrder", async () => {
@@ -238,15 +802,78 @@ describe("instructions", () => {
     );
   });
 
+  it("proposeAuthority", async () => {
+    await proposeAuthority(ctx, ctx.admin, ctx.user4.publicKey);
+
+    const ido = await ctx.program.account.ido.fetch(ctx.ido);
+    expect(ido.admin).to.eql(ctx.admin.publicKey);
+    expect(ido.pendingAuthority).to.eql(ctx.user4.publicKey);
+  });
+
+  it("acceptAuthority", async () => {
+    await expect(acceptAuthority(ctx, ctx.user3)).to.be.rejectedWith(
+      "NotPendingAuthority"
+    );
+
+    await acceptAuthority(ctx, ctx.user4);
+
+    let ido = await ctx.program.account.ido.fetch(ctx.ido);
+    expect(ido.admin).to.eql(ctx.user4.publicKey);
+    expect(ido.pendingAuthority).to.eql(null);
+
+    await proposeAuthority(ctx, ctx.user4, ctx.admin.publicKey);
+    await acceptAuthority(ctx, ctx.admin);
+
+    ido = await ctx.program.account.ido.fetch(ctx.ido);
+    expect(ido.admin).to.eql(ctx.admin.publicKey);
+  });
+
   it("withdrawIdoUsdc", async () => {
     await withdrawIdoUsdc(ctx);
 
     expect(await ctx.idoUsdc.amount(ctx)).to.eql(0);
     expect(
-      await (await ctx.usdcATA(ctx.idoAuthority.publicKey)).amount(ctx)
+      await (await ctx.usdcATA(ctx.treasurer.publicKey)).amount(ctx)
     ).to.not.eql(0);
   });
 
+  it("crank", async () => {
+    await crank(ctx);
+
+    let ido = await ctx.program.account.ido.fetch(ctx.ido);
+    expect(ido.state).to.eql({ saleRound: {} });
+    expect(ido.saleRoundsStarted).to.eql(2);
+    expect(await ctx.idoAcdm.amount(ctx)).to.not.eql(0);
+
+    await expect(crank(ctx)).to.be.rejectedWith("CannotEndRound");
+
+    await sleep(ROUND_TIME + 1);
+    await crank(ctx);
+
+    ido = await ctx.program.account.ido.fetch(ctx.ido);
+    expect(ido.state).to.eql({ tradeRound: {} });
+    expect(await ctx.idoAcdm.amount(ctx)).to.eql(0);
+
+    await sleep(ROUND_TIME + 1);
+  });
+
+  it("closeExpiredOrder", async () => {
+    await expect(
+      redeemOrder(ctx, expiringOrderId, new BN(1), ctx.user2)
+    ).to.be.rejectedWith("OrderExpired");
+
+    await closeExpiredOrder(ctx, expiringOrderId);
+
+    expect(await (await ctx.acdmATA(ctx.user3.publicKey)).amount(ctx)).to.eql(
+      buyAmount + fillAmount + matchAmount + sweepAmount
+    );
+    expect(
+      await ctx.program.account.order.fetchNullable(
+        await ctx.order(expiringOrderId)
+      )
+    ).to.eql(null);
+  });
+
   it("endIdo", async () => {
     await endIdo(ctx);
 
@@ -254,3 +881,161 @@ describe("instructions", () => {
     expect(ido.state).to.eql({ over: {} });
   });
 });
+
+describe("transfer fees", () => {
+  // USDC withholds 1% of every transfer
+  const feeCtx = new Context(100);
+
+  it("pays referers what the referral vault received", async () => {
+    await feeCtx.setup();
+    await initialize(feeCtx, ROUND_TIME, config);
+    await setRoles(
+      feeCtx,
+      feeCtx.operator.publicKey,
+      feeCtx.treasurer.publicKey
+    );
+    await registerMember(feeCtx, feeCtx.user2, null);
+    await registerMember(feeCtx, feeCtx.user1, feeCtx.user2.publicKey);
+    await startSaleRound(feeCtx);
+
+    const buyAmount = 1_000;
+    await mintTo(
+      feeCtx,
+      await feeCtx.usdcATA(feeCtx.user1.publicKey),
+      feeCtx.usdcMintAuthority,
+      usdc(buyAmount, INITIAL_PRICE)
+    );
+    await buyAcdm(feeCtx, new BN(buyAmount), feeCtx.user1);
+
+    const received = await feeCtx.referralUsdc.amount(feeCtx);
+    expect(received).to.eql(
+      (usdc(buyAmount, INITIAL_PRICE) * 5 * 99) / 10_000
+    );
+
+    const member2 = await feeCtx.program.account.member.fetch(
+      await feeCtx.member(feeCtx.user2.publicKey)
+    );
+    expect(member2.rewards.toNumber()).to.eql(received);
+
+    await claimReferralRewards(feeCtx, feeCtx.user2);
+    expect(await feeCtx.referralUsdc.amount(feeCtx)).to.eql(0);
+  });
+});
+
+describe("self trading", () => {
+  const selfCtx = new Context();
+
+  it("keeps both stats of a seller redeeming their own order", async () => {
+    await selfCtx.setup();
+    await initialize(selfCtx, ROUND_TIME, config);
+    await setRoles(
+      selfCtx,
+      selfCtx.operator.publicKey,
+      selfCtx.treasurer.publicKey
+    );
+    await registerMember(selfCtx, selfCtx.user1, null);
+    await startSaleRound(selfCtx);
+
+    const buyAmount = 100;
+    const redeemAmount = 40;
+    await mintTo(
+      selfCtx,
+      await selfCtx.usdcATA(selfCtx.user1.publicKey),
+      selfCtx.usdcMintAuthority,
+      usdc(buyAmount + redeemAmount, INITIAL_PRICE)
+    );
+    await buyAcdm(selfCtx, new BN(buyAmount), selfCtx.user1);
+    await sleep(ROUND_TIME + 1);
+    await startTradeRound(selfCtx);
+
+    const orderId = await addOrder(
+      selfCtx,
+      new BN(buyAmount),
+      new BN(INITIAL_PRICE),
+      selfCtx.user1
+    );
+    await redeemOrder(selfCtx, orderId, new BN(redeemAmount), selfCtx.user1);
+
+    const member = await selfCtx.program.account.member.fetch(
+      await selfCtx.member(selfCtx.user1.publicKey)
+    );
+    expect(member.acdmSold.toNumber()).to.eql(redeemAmount);
+    expect(member.acdmBought.toNumber()).to.eql(buyAmount + redeemAmount);
+  });
+});
+
+describe("migration", () => {
+  // the IDO of the first release, see tests/fixtures/legacy
+  const legacyCtx = new Context();
+  // prices of the first release were in USDC base units per ACDM base unit
+  const LEGACY_ACDM_PRICE = 1_000;
+  const LEGACY_ORDER_PRICE = 1_500;
+
+  it("migrateIdo", async () => {
+    await legacyCtx.setupLegacy();
+
+    // members of the first release can't be moved into any other IDO
+    await expect(
+      migrateMember(ctx, legacyCtx.user1.publicKey)
+    ).to.be.rejectedWith("NotMigrated");
+
+    await migrateIdo(legacyCtx, config);
+
+    const ido = await legacyCtx.program.account.ido.fetch(legacyCtx.ido);
+    expect(ido.version).to.eql(1);
+    expect(ido.migrated).to.eql(true);
+    expect(ido.state).to.eql({ tradeRound: {} });
+    expect(ido.acdmPrice.toNumber()).to.eql(
+      LEGACY_ACDM_PRICE * 10 ** ACDM_DECIMALS
+    );
+    expect(ido.orders.toNumber()).to.eql(1);
+    expect(ido.saleRoundsStarted).to.eql(1);
+
+    expect(await legacyCtx.idoAcdm.amount(legacyCtx)).to.eql(250);
+    expect(await legacyCtx.idoUsdc.amount(legacyCtx)).to.eql(4_750_000);
+    expect(
+      (await getMint(legacyCtx.connection, legacyCtx.acdmMint)).mintAuthority
+    ).to.eql(legacyCtx.ido);
+    expect(
+      await legacyCtx.connection.getAccountInfo(
+        await findPDA(legacyCtx, [Buffer.from("ido")])
+      )
+    ).to.eql(null);
+  });
+
+  it("migrateOrder", async () => {
+    await migrateOrder(legacyCtx, new BN(0));
+
+    const order = await legacyCtx.program.account.order.fetch(
+      await legacyCtx.order(new BN(0))
+    );
+    expect(order.version).to.eql(1);
+    expect(order.authority).to.eql(legacyCtx.user1.publicKey);
+    expect(order.price.toNumber()).to.eql(
+      LEGACY_ORDER_PRICE * 10 ** ACDM_DECIMALS
+    );
+    expect(order.originalAmount.toNumber()).to.eql(1_000);
+    expect(order.filledAmount.toNumber()).to.eql(0);
+    expect(
+      await (await legacyCtx.orderAcdm(new BN(0))).amount(legacyCtx)
+    ).to.eql(1_000);
+  });
+
+  it("migrateMember", async () => {
+    const fetchLegacyMember = async (user: Keypair) =>
+      await legacyCtx.program.account.member.fetch(
+        await legacyCtx.member(user.publicKey)
+      );
+
+    await migrateMember(legacyCtx, legacyCtx.user1.publicKey);
+    await migrateMember(legacyCtx, legacyCtx.user2.publicKey);
+    await migrateMember(legacyCtx, legacyCtx.user3.publicKey);
+
+    expect((await fetchLegacyMember(legacyCtx.user1)).referer).to.eql(null);
+    // the first release let user2 refer themselves, which is dropped
+    expect((await fetchLegacyMember(legacyCtx.user2)).referer).to.eql(null);
+    expect((await fetchLegacyMember(legacyCtx.user3)).referer).to.eql(
+      legacyCtx.user1.publicKey
+    );
+  });
+});
@@ -1,38 +1,101 @@
 import {
   burn,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  ExtensionType,
   getAccount,
+  getMintLen,
   getOrCreateAssociatedTokenAccount,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
-import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  Keypair,
+  PublicKey,
+  sendAndConfirmTransaction,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
 import { Context } from "./ctx";
 import * as token from "@solana/spl-token";
 import { PROGRAM_ID as TOKEN_METADATA_PROGRAM_ID } from "@metaplex-foundation/mpl-token-metadata";
 
 export class TokenAccount extends PublicKey {
   mint: PublicKey;
+  programId: PublicKey;
 
-  constructor(address: PublicKey, mint: PublicKey) {
+  constructor(
+    address: PublicKey,
+    mint: PublicKey,
+    programId: PublicKey = TOKEN_PROGRAM_ID
+  ) {
     super(address);
     this.mint = mint;
+    this.programId = programId;
   }
 
   async amount(ctx: Context): Promise<number> {
-    return Number((await getAccount(ctx.connection, this)).amount);
+    return Number(
+      (await getAccount(ctx.connection, this, undefined, this.programId)).amount
+    );
   }
 }
 
 export async function createMint(
   ctx: Context,
   authority: Keypair,
-  decimals: number
+  decimals: number,
+  programId: PublicKey = TOKEN_PROGRAM_ID
 ): Promise<PublicKey> {
   return await token.createMint(
     ctx.connection,
     ctx.payer,
     authority.publicKey,
     undefined,
-    decimals
+    decimals,
+    undefined,
+    undefined,
+    programId
+  );
+}
+
+// a Token-2022 mint withholding `feeBps` of every transfer
+export async function createMintWithTransferFee(
+  ctx: Context,
+  authority: Keypair,
+  decimals: number,
+  feeBps: number
+): Promise<PublicKey> {
+  const mint = new Keypair();
+  const space = getMintLen([ExtensionType.TransferFeeConfig]);
+
+  const tx = new Transaction().add(
+    SystemProgram.createAccount({
+      fromPubkey: ctx.payer.publicKey,
+      newAccountPubkey: mint.publicKey,
+      space,
+      lamports: await ctx.connection.getMinimumBalanceForRentExemption(space),
+      programId: TOKEN_2022_PROGRAM_ID,
+    }),
+    createInitializeTransferFeeConfigInstruction(
+      mint.publicKey,
+      authority.publicKey,
+      authority.publicKey,
+      feeBps,
+      BigInt(Number.MAX_SAFE_INTEGER),
+      TOKEN_2022_PROGRAM_ID
+    ),
+    createInitializeMintInstruction(
+      mint.publicKey,
+      decimals,
+      authority.publicKey,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    )
   );
+  await sendAndConfirmTransaction(ctx.connection, tx, [ctx.payer, mint]);
+
+  return mint.publicKey;
 }
 
 export async function mintTo(
@@ -47,14 +110,18 @@ export async function mintTo(
     destination.mint,
     destination,
     mintAuthority,
-    amount
+    amount,
+    [],
+    undefined,
+    destination.programId
   );
 }
 
 export async function findATA(
   ctx: Context,
   owner: PublicKey,
-  mint: PublicKey
+  mint: PublicKey,
+  programId: PublicKey = TOKEN_PROGRAM_ID
 ): Promise<TokenAccount> {
   const address = (
     await getOrCreateAssociatedTokenAccount(
@@ -62,11 +129,14 @@ export async function findATA(
       ctx.payer,
       mint,
       owner,
-      true
+      true,
+      undefined,
+      undefined,
+      programId
     )
   ).address;
 
-  return new TokenAccount(address, mint);
+  return new TokenAccount(address, mint, programId);
 }
 
 export async function burnAll(
@@ -80,7 +150,10 @@ export async function burnAll(
     from,
     from.mint,
     owner,
-    await from.amount(ctx)
+    await from.amount(ctx),
+    [],
+    undefined,
+    from.programId
   );
 }
 
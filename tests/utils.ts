@@ -38,3 +38,7 @@ export async function findPDA(
 ): Promise<PublicKey> {
   return (await PublicKey.findProgramAddress(seeds, ctx.program.programId))[0];
 }
+
+export async function sleep(seconds: number): Promise<void> {
+  await new Promise((resolve) => setTimeout(resolve, seconds * 1000));
+}